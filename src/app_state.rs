@@ -1,4 +1,4 @@
-use sdl2::video::Window;
+use sdl2::video::{FullscreenType, Window};
 use skia_safe::Point;
 
 pub const MIN_ZOOM: f32 = 32.0;
@@ -43,4 +43,26 @@ impl AppState {
             _target: Point::new(0.0, 50.0),
         }
     }
+
+    /// Returns whether the size actually changed, SDL sends more than one event per resize.
+    pub fn resize(&mut self, width: i32, height: i32) -> bool {
+        if width == self.gfx.width && height == self.gfx.height {
+            return false;
+        }
+        self.gfx.width = width;
+        self.gfx.height = height;
+        self.gfx._half_width = width / 2;
+        self.gfx._half_height = height / 2;
+        true
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        let fullscreen = match self.gfx.window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+        if let Err(e) = self.gfx.window.set_fullscreen(fullscreen) {
+            eprintln!("Could not change to {:?}: {}", fullscreen, e);
+        }
+    }
 }
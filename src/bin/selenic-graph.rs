@@ -0,0 +1,33 @@
+use selenic_echo::content::export::{export_dialogue, export_map, GraphFormat};
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use std::env;
+use std::path::Path;
+use std::process::exit;
+
+const USAGE: &str = "usage: selenic-graph dialogue|map [--mermaid]";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let format = if args.iter().any(|arg| arg == "--mermaid") {
+        GraphFormat::Mermaid
+    } else {
+        GraphFormat::Dot
+    };
+
+    let content = match load(Path::new(CONTENT_DIR)) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+
+    match args.first().map(|arg| arg.as_str()) {
+        Some("dialogue") => print!("{}", export_dialogue(&content, format)),
+        Some("map") => print!("{}", export_map(&content, format)),
+        _ => {
+            eprintln!("{}", USAGE);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use selenic_echo::content::lint::lint;
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use std::env;
+use std::path::PathBuf;
+use std::process::exit;
+
+fn main() {
+    let dir = PathBuf::from(env::args().nth(1).unwrap_or(CONTENT_DIR.to_string()));
+    let content = match load(&dir) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+
+    let problems = lint(&content);
+    for problem in problems.iter() {
+        println!("{}: {}", dir.display(), problem);
+    }
+    if !problems.is_empty() {
+        exit(1);
+    }
+}
@@ -0,0 +1,28 @@
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use selenic_echo::tty;
+use std::env;
+use std::io::{stdout, ErrorKind, IsTerminal};
+use std::path::Path;
+use std::process::exit;
+
+fn main() {
+    let content = match load(Path::new(CONTENT_DIR)) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+
+    // Styling only makes sense on a terminal, --plain forces it off
+    let plain = env::args().any(|arg| arg == "--plain");
+    match tty::run(content, !plain && stdout().is_terminal()) {
+        // Whatever was reading the output has had enough, nothing more to say
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+        Ok(()) => {}
+    }
+}
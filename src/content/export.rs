@@ -0,0 +1,176 @@
+use crate::content::loader::Content;
+use crate::game::conditions::Condition;
+use crate::markup::{parse, QueueItem};
+use crate::printer::PrintStyle;
+
+const WRAP_WIDTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+/// Boxes and arrows, independent of the output format.
+struct Graph {
+    nodes: Vec<(String, String)>,
+    edges: Vec<(String, String, String)>,
+}
+
+/// Every conversation, starting from the person it belongs to. Nodes show the speaker and what they say,
+/// edges the option text and the conditions for it to be offered.
+pub fn export_dialogue(content: &Content, format: GraphFormat) -> String {
+    let mut graph = Graph {
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    let mut roots = content.dialogue_roots.iter().collect::<Vec<_>>();
+    roots.sort_by_key(|(person, _)| person.name());
+    for (person, root) in roots {
+        let id = format!("person_{}", person.name().to_lowercase());
+        graph.nodes.push((id.clone(), format!("Talk to {}", person.name())));
+        graph.edges.push((id, root.as_str().to_string(), String::new()));
+    }
+
+    let mut nodes = content.dialogues.values().collect::<Vec<_>>();
+    nodes.sort_by_key(|node| node.id);
+    for node in nodes {
+        graph
+            .nodes
+            .push((node.id.as_str().to_string(), wrap(&format!("{}: {}", node.speaker.name(), plain(&node.text)))));
+        for option in node.options.iter() {
+            let label = wrap(&plain(&option.text)) + &when(option.condition.as_ref());
+            graph.edges.push((node.id.as_str().to_string(), option.next.as_str().to_string(), label));
+        }
+    }
+
+    render(&graph, "dialogue", format)
+}
+
+/// Locations joined by their exits, labelled with the direction and whether they start locked.
+pub fn export_map(content: &Content, format: GraphFormat) -> String {
+    let mut graph = Graph {
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    let mut locations = content.locations.values().collect::<Vec<_>>();
+    locations.sort_by_key(|location| location.id);
+    for location in locations {
+        graph.nodes.push((location.id.as_str().to_string(), location.name.clone()));
+        for exit in location.exits.iter() {
+            let mut label = exit.direction.name().to_string();
+            if exit.locked.is_some() {
+                label.push_str(" (locked)");
+            }
+            label.push_str(&when(exit.visible_when.as_ref()));
+            graph.edges.push((location.id.as_str().to_string(), exit.to.as_str().to_string(), label));
+        }
+    }
+
+    render(&graph, "map", format)
+}
+
+fn when(condition: Option<&Condition>) -> String {
+    match condition {
+        Some(condition) => format!("\nwhen {}", condition.describe()),
+        None => String::new(),
+    }
+}
+
+/// Text without its markup tags, as it reads on screen.
+fn plain(text: &str) -> String {
+    let Ok(items) = parse(text, PrintStyle::Normal) else {
+        return text.to_string();
+    };
+    let mut result = String::new();
+    for item in items.iter() {
+        match item {
+            QueueItem::Word(runs) => {
+                if !result.is_empty() && !result.ends_with('\n') {
+                    result.push(' ');
+                }
+                result.extend(runs.iter().map(|run| run.text.as_str()));
+            }
+            QueueItem::LineBreak | QueueItem::Paragraph => result.push('\n'),
+            QueueItem::Pause(_) => {}
+        }
+    }
+    result
+}
+
+/// A Mermaid node id for any content id. The prefix keeps clear of keywords like end, and anything but a letter or
+/// digit is written as _ and its hex bytes, with __ for _ itself, so different ids never come out the same.
+fn mermaid_id(id: &str) -> String {
+    let mut result = "n_".to_string();
+    for c in id.chars() {
+        match c {
+            '_' => result.push_str("__"),
+            c if c.is_ascii_alphanumeric() => result.push(c),
+            c => {
+                let mut bytes = [0; 4];
+                for byte in c.encode_utf8(&mut bytes).bytes() {
+                    result.push_str(&format!("_{:02x}", byte));
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Break long text into lines so the boxes stay readable.
+fn wrap(text: &str) -> String {
+    let mut lines = Vec::<String>::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + word.len() < WRAP_WIDTH => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines.join("\n")
+}
+
+fn render(graph: &Graph, name: &str, format: GraphFormat) -> String {
+    let mut result = String::new();
+    match format {
+        GraphFormat::Dot => {
+            let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            result.push_str(&format!("digraph {} {{\n    node [shape=box];\n", name));
+            for (id, label) in graph.nodes.iter() {
+                result.push_str(&format!("    \"{}\" [label=\"{}\"];\n", escape(id), escape(label)));
+            }
+            for (from, to, label) in graph.edges.iter() {
+                result.push_str(&format!("    \"{}\" -> \"{}\"", escape(from), escape(to)));
+                if !label.is_empty() {
+                    result.push_str(&format!(" [label=\"{}\"]", escape(label)));
+                }
+                result.push_str(";\n");
+            }
+            result.push_str("}\n");
+        }
+        GraphFormat::Mermaid => {
+            let escape = |text: &str| text.replace('"', "#quot;").replace('\n', "<br/>");
+            result.push_str("flowchart TD\n");
+            for (node, label) in graph.nodes.iter() {
+                result.push_str(&format!("    {}[\"{}\"]\n", mermaid_id(node), escape(label)));
+            }
+            for (from, to, label) in graph.edges.iter() {
+                if label.is_empty() {
+                    result.push_str(&format!("    {} --> {}\n", mermaid_id(from), mermaid_id(to)));
+                } else {
+                    result.push_str(&format!(
+                        "    {} -->|\"{}\"| {}\n",
+                        mermaid_id(from),
+                        escape(label),
+                        mermaid_id(to)
+                    ));
+                }
+            }
+        }
+    }
+    result
+}
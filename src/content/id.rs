@@ -0,0 +1,15 @@
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+static IDS: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+/// Ids live for the whole game, so each distinct one is leaked once and shared from then on.
+pub fn intern(id: &str) -> &'static str {
+    let mut ids = IDS.get_or_init(Default::default).lock().unwrap();
+    if let Some(existing) = ids.get(id) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(id.to_string().into_boxed_str());
+    ids.insert(leaked);
+    leaked
+}
@@ -0,0 +1,87 @@
+use crate::content::loader::Content;
+use crate::dialogue::logic::DialogueNodeID;
+use crate::location::locations::LocationID;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Problems that load fine but are almost certainly mistakes, sorted so the output is stable.
+pub fn lint(content: &Content) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    // Locations the player can never walk to
+    let reached = reach(vec![content.start_location], |id: LocationID| {
+        content
+            .locations
+            .get(&id)
+            .map(|location| location.exits.iter().map(|exit| exit.to).collect())
+            .unwrap_or_default()
+    });
+    let mut locations = content.locations.keys().filter(|id| !reached.contains(id)).collect::<Vec<_>>();
+    locations.sort();
+    for id in locations {
+        problems.push(format!("location \"{:?}\" can't be reached from \"{:?}\"", id, content.start_location));
+    }
+
+    // Dialogue nobody can get to from the start of a conversation
+    let next = |id: DialogueNodeID| {
+        content
+            .dialogues
+            .get(&id)
+            .map(|node| node.options.iter().map(|option| option.next).collect())
+            .unwrap_or_default()
+    };
+    let reached = reach(content.dialogue_roots.values().copied().collect(), next);
+    let mut nodes = content.dialogues.keys().filter(|id| !reached.contains(id)).collect::<Vec<_>>();
+    nodes.sort();
+    for id in nodes {
+        problems.push(format!("dialogue node \"{:?}\" can't be reached from any conversation", id));
+    }
+
+    // Conversations that go round in circles, only saying goodbye gets the player out
+    let mut nodes = content.dialogues.keys().collect::<Vec<_>>();
+    nodes.sort();
+    for id in nodes {
+        let ahead = reach(vec![*id], next);
+        let ends = ahead.iter().any(|id| content.dialogues.get(id).is_some_and(|node| node.options.is_empty()));
+        if !ends {
+            problems.push(format!("dialogue node \"{:?}\" only leads back round, it never reaches an ending", id));
+        }
+    }
+
+    // Narrations nothing prints
+    let mut narrations = content
+        .narrations
+        .keys()
+        .filter(|id| {
+            **id != content.start_narration
+                && content.oxygen.as_ref().is_none_or(|oxygen| oxygen.out != **id)
+                && !content.triggers.iter().any(|trigger| trigger.narration == Some(**id))
+                && !content.locations.values().any(|location| location.narration_id == **id)
+        })
+        .collect::<Vec<_>>();
+    narrations.sort();
+    for id in narrations {
+        problems.push(format!("narration \"{:?}\" isn't used by any location or the game", id));
+    }
+
+    // Items the player has no way to type
+    for item in content.items.iter().filter(|item| item.names.is_empty()) {
+        problems.push(format!("item \"{:?}\" has no names to refer to it by", item.props_id));
+    }
+
+    problems
+}
+
+/// Everything that can be got to from the start by repeatedly following next.
+fn reach<T: Copy + Eq + Hash>(start: Vec<T>, next: impl Fn(T) -> Vec<T>) -> HashSet<T> {
+    let mut seen = start.iter().copied().collect::<HashSet<T>>();
+    let mut todo = start;
+    while let Some(id) = todo.pop() {
+        for to in next(id) {
+            if seen.insert(to) {
+                todo.push(to);
+            }
+        }
+    }
+    seen
+}
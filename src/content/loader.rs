@@ -0,0 +1,932 @@
+use crate::dialogue::logic::{DialogueNode, DialogueNodeID, DialogueOption, DialoguePersonID};
+use crate::dialogue::people::{Person, ScheduledMove, Whereabouts};
+use crate::game::conditions::Condition;
+use crate::game::effects::Effect;
+use crate::game::expression::{parse_condition, Names};
+use crate::game::oxygen::{Oxygen, OxygenWarning};
+use crate::game::triggers::{Event, Trigger};
+use crate::game::variables::{references, Value, Variable, VariableType};
+use crate::location::direction::Direction;
+use crate::location::exit::Exit;
+use crate::location::locations::{Location, LocationID};
+use crate::markup::parse;
+use crate::narration::narrations::{directives, parse_blocks, Narration, NarrationID};
+use crate::printer::PrintStyle;
+use crate::props::enums::{PropTypes, PropsID};
+use crate::props::item::{Item, Usage};
+use crate::props::placement::Placement;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::fs;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+
+pub const CONTENT_DIR: &str = "content";
+
+/// Everything the writers define, read from the content directory at startup.
+pub struct Content {
+    pub start_location: LocationID,
+    pub start_narration: NarrationID,
+    pub locations: HashMap<LocationID, Location>,
+    pub narrations: HashMap<NarrationID, Narration>,
+    pub dialogues: HashMap<DialogueNodeID, DialogueNode>,
+    pub dialogue_roots: HashMap<DialoguePersonID, DialogueNodeID>,
+    pub items: Vec<Item>,
+    pub variables: Vec<Variable>,
+    pub oxygen: Option<Oxygen>,
+    pub triggers: Vec<Trigger>,
+    pub people: Vec<Person>,
+}
+
+#[derive(Deserialize)]
+struct GameFile {
+    start_location: Spanned<LocationID>,
+    start_narration: Spanned<NarrationID>,
+    oxygen: Option<OxygenEntry>,
+}
+
+#[derive(Deserialize)]
+struct OxygenEntry {
+    variable: Spanned<String>,
+    seconds_per_percent: Spanned<f32>,
+    out: Spanned<NarrationID>,
+    #[serde(default)]
+    warning: Vec<WarningEntry>,
+}
+
+#[derive(Deserialize)]
+struct WarningEntry {
+    below: i64,
+    text: Spanned<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct VariablesFile {
+    #[serde(default)]
+    variable: Vec<VariableEntry>,
+}
+
+#[derive(Deserialize)]
+struct VariableEntry {
+    name: Spanned<String>,
+    #[serde(rename = "type")]
+    kind: Spanned<String>,
+    #[serde(default)]
+    values: Vec<String>,
+    start: Spanned<Value>,
+}
+
+#[derive(Deserialize, Default)]
+struct PeopleFile {
+    #[serde(default)]
+    person: Vec<PersonEntry>,
+}
+
+#[derive(Deserialize)]
+struct PersonEntry {
+    id: Spanned<DialoguePersonID>,
+    description: Spanned<String>,
+    start: Option<Spanned<String>>,
+    #[serde(default)]
+    states: Vec<String>,
+    state: Option<Spanned<String>>,
+    #[serde(default)]
+    schedule: Vec<MoveEntry>,
+}
+
+#[derive(Deserialize)]
+struct MoveEntry {
+    turn: u32,
+    to: Spanned<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct TriggersFile {
+    #[serde(default)]
+    trigger: Vec<TriggerEntry>,
+}
+
+#[derive(Deserialize)]
+struct TriggerEntry {
+    id: Spanned<String>,
+    enter: Option<Spanned<LocationID>>,
+    turns: Option<u32>,
+    seconds: Option<f32>,
+    taken: Option<Spanned<PropsID>>,
+    when: Option<Spanned<String>>,
+    narration: Option<Spanned<NarrationID>>,
+    text: Option<Spanned<String>>,
+    talk: Option<DialoguePersonID>,
+    #[serde(default)]
+    then: Vec<Spanned<Effect>>,
+}
+
+#[derive(Deserialize)]
+struct LocationsFile {
+    location: Vec<LocationEntry>,
+}
+
+#[derive(Deserialize)]
+struct LocationEntry {
+    id: Spanned<LocationID>,
+    name: Spanned<String>,
+    narration: Spanned<NarrationID>,
+    #[serde(default)]
+    exit: Vec<ExitEntry>,
+}
+
+#[derive(Deserialize)]
+struct ExitEntry {
+    direction: Spanned<Direction>,
+    to: Spanned<LocationID>,
+    #[serde(default)]
+    names: Vec<String>,
+    locked: Option<Spanned<String>>,
+    when: Option<Spanned<String>>,
+    #[serde(default)]
+    then: Vec<Spanned<Effect>>,
+}
+
+#[derive(Deserialize)]
+struct ItemsFile {
+    item: Vec<ItemEntry>,
+}
+
+#[derive(Deserialize)]
+struct ItemEntry {
+    id: Spanned<PropsID>,
+    name: Spanned<String>,
+    description: Spanned<String>,
+    #[serde(default)]
+    types: Vec<PropTypes>,
+    start: Option<Spanned<Placement>>,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(rename = "use")]
+    usage: Option<UsageEntry>,
+}
+
+#[derive(Deserialize)]
+struct UsageEntry {
+    text: Spanned<String>,
+    #[serde(default)]
+    then: Vec<Spanned<Effect>>,
+}
+
+#[derive(Deserialize)]
+struct DialogueFile {
+    person: DialoguePersonID,
+    root: Spanned<DialogueNodeID>,
+    node: Vec<NodeEntry>,
+}
+
+#[derive(Deserialize)]
+struct NodeEntry {
+    id: Spanned<DialogueNodeID>,
+    speaker: DialoguePersonID,
+    text: Spanned<String>,
+    #[serde(default)]
+    option: Vec<OptionEntry>,
+}
+
+#[derive(Deserialize)]
+struct OptionEntry {
+    text: Spanned<String>,
+    next: Spanned<DialogueNodeID>,
+    when: Option<Spanned<String>>,
+    #[serde(default)]
+    then: Vec<Spanned<Effect>>,
+}
+
+/// A content file's name and text, kept so errors can point at a line.
+struct SourceFile {
+    path: PathBuf,
+    text: String,
+}
+
+impl SourceFile {
+    fn at<U>(&self, spanned: &Spanned<U>) -> String {
+        self.at_offset(spanned.span().start)
+    }
+
+    fn at_offset(&self, offset: usize) -> String {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.text[..offset].matches('\n').count() + 1;
+        format!("{}:{}", self.path.display(), line)
+    }
+
+    /// Where a position in a string's value is in the file, past the quotes and any escapes before it.
+    fn at_value(&self, value: &Spanned<String>, offset: usize) -> String {
+        let span = value.span();
+        let raw = &self.text[span.clone()];
+        let quotes = if raw.starts_with("\"\"\"") || raw.starts_with("'''") {
+            3
+        } else {
+            1
+        };
+        let literal = raw.starts_with('\'');
+        let mut chars = raw[quotes..].char_indices().peekable();
+        // A line break right after the opening quotes isn't part of the value
+        if quotes == 3 {
+            chars.next_if(|(_, c)| *c == '\r');
+            chars.next_if(|(_, c)| *c == '\n');
+        }
+
+        let mut decoded = 0;
+        while let Some((at, c)) = chars.next() {
+            if decoded >= offset {
+                return self.at_offset(span.start + quotes + at);
+            }
+            decoded += match c {
+                '\\' if !literal => match chars.next() {
+                    Some((_, 'u')) => unicode_len(chars.by_ref().take(4).map(|(_, c)| c).collect()),
+                    Some((_, 'U')) => unicode_len(chars.by_ref().take(8).map(|(_, c)| c).collect()),
+                    // A backslash at the end of a line skips the line break and the indent after it
+                    Some((_, c)) if c.is_whitespace() => {
+                        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                        continue;
+                    }
+                    _ => 1,
+                },
+                c => c.len_utf8(),
+            };
+        }
+        self.at_offset(span.end)
+    }
+}
+
+/// How long the character a \\u escape stands for is once decoded.
+fn unicode_len(hex: String) -> usize {
+    u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).map_or(1, char::len_utf8)
+}
+
+/// Ids defined anywhere in the content, for checking references against.
+#[derive(Default)]
+struct Defined {
+    locations: HashSet<LocationID>,
+    narrations: HashSet<NarrationID>,
+    nodes: HashSet<DialogueNodeID>,
+    props: HashSet<PropsID>,
+    exits: HashSet<(LocationID, Direction)>,
+    variables: HashMap<String, VariableType>,
+    people: HashMap<DialoguePersonID, VariableType>,
+}
+
+impl Defined {
+    /// Markup mistakes and unknown {variables} in a string from a file.
+    fn check_text(&self, source: &SourceFile, text: &Spanned<String>, errors: &mut Vec<String>) {
+        if let Err(e) = parse(text.get_ref(), PrintStyle::Normal) {
+            errors.push(format!("{}: {}", source.at(text), e.message));
+        }
+        self.check_references(text.get_ref(), |at| source.at_value(text, at), errors);
+    }
+
+    /// Unknown {variables}, with where a position in the text is in its file.
+    fn check_references(&self, text: &str, at: impl Fn(usize) -> String, errors: &mut Vec<String>) {
+        for (offset, name) in references(text) {
+            if !self.variables.contains_key(name) {
+                errors.push(format!("{}: unknown variable \"{}\"", at(offset), name));
+            }
+        }
+    }
+
+    /// The variable has to be declared, and hold the kind of value it's used with.
+    fn check_variable(&self, name: &str, fits: impl Fn(&VariableType) -> bool, at: &str, errors: &mut Vec<String>) {
+        match self.variables.get(name) {
+            Some(kind) if fits(kind) => {}
+            Some(kind) => {
+                errors.push(format!("{}: variable \"{}\" is {}, it can't be used like that", at, name, kind.name()))
+            }
+            None => errors.push(format!("{}: unknown variable \"{}\"", at, name)),
+        }
+    }
+
+    /// A condition written as an expression in a string, pointing at the line it went wrong on.
+    fn condition(&self, source: &SourceFile, text: &Spanned<String>, errors: &mut Vec<String>) -> Option<Condition> {
+        match parse_condition(text.get_ref(), self) {
+            Ok(condition) => Some(condition),
+            Err(e) => {
+                errors.push(format!("{}: {}", source.at_value(text, e.offset), e.message));
+                None
+            }
+        }
+    }
+
+    fn check_effect(&self, effect: &Effect, at: &str, errors: &mut Vec<String>) {
+        match effect {
+            Effect::SetFlag(name) | Effect::ClearFlag(name) => {
+                self.check_variable(name, |kind| *kind == VariableType::Bool, at, errors)
+            }
+            Effect::Set(name, value) => self.check_variable(name, |kind| kind.accepts(value), at, errors),
+            Effect::Add(name, _) => self.check_variable(name, |kind| *kind == VariableType::Int, at, errors),
+            Effect::GiveItem(id) | Effect::TakeItem(id) => check(&self.props, *id, "item", at, errors),
+            Effect::UnlockExit(id, direction) => {
+                check(&self.locations, *id, "location", at, errors);
+                if self.locations.contains(id) && !self.exits.contains(&(*id, *direction)) {
+                    errors.push(format!("{}: there is no exit {} from \"{:?}\"", at, direction.name(), id));
+                }
+            }
+            Effect::SetState(id, state) => match self.people.get(id) {
+                Some(states) if states.accepts(&Value::Text(state.clone())) => {}
+                Some(_) => errors.push(format!("{}: {} has no state \"{}\"", at, id.name(), state)),
+                None => self.check_person(*id, at, errors),
+            },
+            Effect::Follow(id) | Effect::Stay(id) => self.check_person(*id, at, errors),
+            Effect::MovePerson(id, location) => {
+                self.check_person(*id, at, errors);
+                check(&self.locations, *location, "location", at, errors);
+            }
+        }
+    }
+
+    fn check_person(&self, id: DialoguePersonID, at: &str, errors: &mut Vec<String>) {
+        if !self.people.contains_key(&id) {
+            errors.push(format!("{}: {} isn't in the people file", at, id.name()));
+        }
+    }
+
+    /// "everywhere", "nowhere" or a location.
+    fn whereabouts(&self, source: &SourceFile, text: &Spanned<String>, errors: &mut Vec<String>) -> Whereabouts {
+        match text.get_ref().as_str() {
+            "everywhere" => Whereabouts::Everywhere,
+            "nowhere" => Whereabouts::Nowhere,
+            id => {
+                let id = LocationID::new(id);
+                check(&self.locations, id, "location", &source.at(text), errors);
+                Whereabouts::At(id)
+            }
+        }
+    }
+
+    fn check_placement(&self, placement: &Placement, at: &str, errors: &mut Vec<String>) {
+        match placement {
+            Placement::Location(id) => check(&self.locations, *id, "location", at, errors),
+            Placement::Inside(id) => check(&self.props, *id, "item", at, errors),
+            Placement::Nowhere | Placement::Player | Placement::Person(_) => {}
+        }
+    }
+}
+
+impl Names for Defined {
+    fn is_location(&self, id: LocationID) -> bool {
+        self.locations.contains(&id)
+    }
+
+    fn is_item(&self, id: PropsID) -> bool {
+        self.props.contains(&id)
+    }
+
+    fn is_node(&self, id: DialogueNodeID) -> bool {
+        self.nodes.contains(&id)
+    }
+
+    fn variable(&self, name: &str) -> Option<&VariableType> {
+        self.variables.get(name)
+    }
+
+    fn person(&self, id: DialoguePersonID) -> Option<&VariableType> {
+        self.people.get(&id)
+    }
+}
+
+fn check<T: Eq + Hash + Debug>(defined: &HashSet<T>, id: T, what: &str, at: &str, errors: &mut Vec<String>) {
+    if !defined.contains(&id) {
+        errors.push(format!("{}: unknown {} \"{:?}\"", at, what, id));
+    }
+}
+
+/// Names are put into markup as they are, so they can't have any of their own.
+fn check_name(source: &SourceFile, name: &Spanned<String>, errors: &mut Vec<String>) {
+    if name.get_ref().contains('<') {
+        errors.push(format!("{}: the name \"{}\" can't contain markup", source.at(name), name.get_ref()));
+    }
+}
+
+fn define<T: Copy + Eq + Hash + Debug>(
+    defined: &mut HashSet<T>,
+    id: T,
+    what: &str,
+    at: &str,
+    errors: &mut Vec<String>,
+) {
+    if !defined.insert(id) {
+        errors.push(format!("{}: {} \"{:?}\" is defined twice", at, what, id));
+    }
+}
+
+fn read<T: DeserializeOwned>(path: PathBuf, errors: &mut Vec<String>) -> Option<(SourceFile, T)> {
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            errors.push(format!("{}: {}", path.display(), e));
+            return None;
+        }
+    };
+    match toml::from_str::<T>(&text) {
+        Ok(data) => Some((
+            SourceFile {
+                path,
+                text,
+            },
+            data,
+        )),
+        Err(e) => {
+            errors.push(format!("{}: {}", path.display(), e.to_string().trim_end()));
+            None
+        }
+    }
+}
+
+/// A file the content can leave out, which reads as empty when it isn't there.
+fn read_optional<T: DeserializeOwned + Default>(path: PathBuf, errors: &mut Vec<String>) -> Option<(SourceFile, T)> {
+    if !path.exists() {
+        return Some((
+            SourceFile {
+                path,
+                text: String::new(),
+            },
+            T::default(),
+        ));
+    }
+    read(path, errors)
+}
+
+/// Files in a directory with the given extension, sorted so errors come out in a stable order.
+fn files(dir: &Path, extension: &str, errors: &mut Vec<String>) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push(format!("{}: {}", dir.display(), e));
+            return Vec::new();
+        }
+    };
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == extension))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+    paths
+}
+
+/// Load and cross-check all content. Every problem found is reported, one per line.
+pub fn load(dir: &Path) -> Result<Content, String> {
+    let mut errors = Vec::new();
+    let game = read::<GameFile>(dir.join("game.toml"), &mut errors);
+    let locations = read::<LocationsFile>(dir.join("locations.toml"), &mut errors);
+    let items = read::<ItemsFile>(dir.join("items.toml"), &mut errors);
+    let variables = read_optional::<VariablesFile>(dir.join("variables.toml"), &mut errors);
+    let triggers = read_optional::<TriggersFile>(dir.join("triggers.toml"), &mut errors);
+    let people = read_optional::<PeopleFile>(dir.join("people.toml"), &mut errors);
+    let dialogues = files(&dir.join("dialogue"), "toml", &mut errors)
+        .into_iter()
+        .filter_map(|path| read::<DialogueFile>(path, &mut errors))
+        .collect::<Vec<(SourceFile, DialogueFile)>>();
+    let narration_files = read_narrations(&dir.join("narration"), &mut errors);
+
+    let (
+        Some((game_file, game)),
+        Some((locations_file, locations)),
+        Some((items_file, items)),
+        Some((variables_file, variables)),
+        Some((triggers_file, triggers)),
+        Some((people_file, people)),
+    ) = (game, locations, items, variables, triggers, people)
+    else {
+        return Err(errors.join("\n"));
+    };
+
+    // Everything that can be referred to
+    let mut defined = Defined {
+        narrations: narration_files.iter().map(|(id, _)| *id).collect(),
+        ..Defined::default()
+    };
+    let variables = load_variables(&variables_file, variables, &mut defined, &mut errors);
+    for entry in locations.location.iter() {
+        define(&mut defined.locations, *entry.id.get_ref(), "location", &locations_file.at(&entry.id), &mut errors);
+        // Every exit, before any effect that unlocks one is checked
+        for exit in entry.exit.iter() {
+            if !defined.exits.insert((*entry.id.get_ref(), *exit.direction.get_ref())) {
+                errors.push(format!(
+                    "{}: \"{:?}\" has two exits {}",
+                    locations_file.at(&exit.direction),
+                    entry.id.get_ref(),
+                    exit.direction.get_ref().name()
+                ));
+            }
+        }
+    }
+    for entry in items.item.iter() {
+        define(&mut defined.props, *entry.id.get_ref(), "item", &items_file.at(&entry.id), &mut errors);
+    }
+    for (source, file) in dialogues.iter() {
+        for node in file.node.iter() {
+            define(&mut defined.nodes, *node.id.get_ref(), "dialogue node", &source.at(&node.id), &mut errors);
+        }
+    }
+
+    let people = load_people(&people_file, people, &mut defined, &mut errors);
+    let narrations = load_narrations(&narration_files, &defined, &mut errors);
+
+    check(
+        &defined.locations,
+        *game.start_location.get_ref(),
+        "location",
+        &game_file.at(&game.start_location),
+        &mut errors,
+    );
+    check(
+        &defined.narrations,
+        *game.start_narration.get_ref(),
+        "narration",
+        &game_file.at(&game.start_narration),
+        &mut errors,
+    );
+
+    let oxygen = game.oxygen.map(|entry| load_oxygen(&game_file, entry, &defined, &mut errors));
+    let locations = load_locations(&locations_file, locations, &defined, &mut errors);
+    let items = load_items(&items_file, items, &defined, &mut errors);
+    let (dialogues, dialogue_roots) = load_dialogues(dialogues, &defined, &mut errors);
+    let triggers = load_triggers(&triggers_file, triggers, &defined, &dialogue_roots, &mut errors);
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    Ok(Content {
+        start_location: game.start_location.into_inner(),
+        start_narration: game.start_narration.into_inner(),
+        locations,
+        narrations,
+        dialogues,
+        dialogue_roots,
+        items,
+        variables,
+        oxygen,
+        triggers,
+        people,
+    })
+}
+
+/// Story variables, declaring each one so the rest of the content can use it.
+fn load_variables(
+    source: &SourceFile,
+    file: VariablesFile,
+    defined: &mut Defined,
+    errors: &mut Vec<String>,
+) -> Vec<Variable> {
+    let mut variable_list = Vec::new();
+    for entry in file.variable.into_iter() {
+        let at = source.at(&entry.name);
+        let kind = match (entry.kind.get_ref().as_str(), entry.values.is_empty()) {
+            ("bool", true) => VariableType::Bool,
+            ("int", true) => VariableType::Int,
+            ("string", true) => VariableType::String,
+            ("enum", false) => VariableType::Enum(entry.values),
+            ("enum", true) => {
+                errors.push(format!("{}: enum \"{}\" needs a list of values", at, entry.name.get_ref()));
+                continue;
+            }
+            ("bool" | "int" | "string", false) => {
+                errors.push(format!("{}: only an enum has a list of values", at));
+                continue;
+            }
+            (kind, _) => {
+                errors.push(format!("{}: unknown type \"{}\"", source.at(&entry.kind), kind));
+                continue;
+            }
+        };
+        if !kind.accepts(entry.start.get_ref()) {
+            errors.push(format!(
+                "{}: {} can't start as {}",
+                source.at(&entry.start),
+                entry.name.get_ref(),
+                entry.start.get_ref()
+            ));
+        }
+        if defined.variables.insert(entry.name.get_ref().clone(), kind.clone()).is_some() {
+            errors.push(format!("{}: variable \"{}\" is defined twice", at, entry.name.get_ref()));
+        }
+        variable_list.push(Variable {
+            name: entry.name.into_inner(),
+            kind,
+            start: entry.start.into_inner(),
+        });
+    }
+    variable_list
+}
+
+/// People, whose states conditions can test.
+fn load_people(source: &SourceFile, file: PeopleFile, defined: &mut Defined, errors: &mut Vec<String>) -> Vec<Person> {
+    let mut person_list = Vec::new();
+    for entry in file.person.into_iter() {
+        let at = source.at(&entry.id);
+        let id = entry.id.into_inner();
+        if defined.people.insert(id, VariableType::Enum(entry.states.clone())).is_some() {
+            errors.push(format!("{}: {} is defined twice", at, id.name()));
+        }
+        defined.check_text(source, &entry.description, errors);
+        let start = match entry.start.as_ref() {
+            Some(start) => defined.whereabouts(source, start, errors),
+            None => Whereabouts::Nowhere,
+        };
+        if let Some(state) = entry.state.as_ref().filter(|state| !entry.states.contains(state.get_ref())) {
+            errors.push(format!("{}: {} has no state \"{}\"", source.at(state), id.name(), state.get_ref()));
+        }
+        let state = match entry.state {
+            Some(state) => state.into_inner(),
+            None => entry.states.first().cloned().unwrap_or_default(),
+        };
+        let schedule = entry
+            .schedule
+            .iter()
+            .map(|step| ScheduledMove {
+                turn: step.turn,
+                to: defined.whereabouts(source, &step.to, errors),
+            })
+            .collect::<Vec<_>>();
+        if schedule.windows(2).any(|steps| steps[0].turn > steps[1].turn) {
+            errors.push(format!("{}: {}'s schedule has to be in order of turn", at, id.name()));
+        }
+        person_list.push(Person {
+            id,
+            description: entry.description.into_inner(),
+            start,
+            state,
+            schedule,
+        });
+    }
+    person_list
+}
+
+/// Narrations are plain text, named after their file.
+fn read_narrations(dir: &Path, errors: &mut Vec<String>) -> Vec<(NarrationID, SourceFile)> {
+    let mut narration_files = Vec::new();
+    for path in files(dir, "txt", errors) {
+        match fs::read_to_string(&path) {
+            Ok(text) => narration_files.push((
+                NarrationID::new(&path.file_stem().unwrap().to_string_lossy()),
+                SourceFile {
+                    path,
+                    text,
+                },
+            )),
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    narration_files
+}
+
+/// Narrations are parsed once everything they can refer to is known.
+fn load_narrations(
+    sources: &[(NarrationID, SourceFile)],
+    defined: &Defined,
+    errors: &mut Vec<String>,
+) -> HashMap<NarrationID, Narration> {
+    let mut narrations = HashMap::new();
+    for (id, source) in sources.iter() {
+        let text = source.text.trim_end();
+        // Markup and {variables} are checked with the {if} blocks blanked out, keeping every offset the same
+        let mut plain = text.to_string();
+        for (start, end, _) in directives(text) {
+            let blank = text[start..end].chars().map(|c| {
+                if c == '\n' {
+                    "\n".to_string()
+                } else {
+                    " ".repeat(c.len_utf8())
+                }
+            });
+            plain.replace_range(start..end, &blank.collect::<String>());
+        }
+        if let Err(e) = parse(&plain, PrintStyle::Normal) {
+            errors.push(format!("{}: {}", source.at_offset(e.offset), e.message));
+        }
+        defined.check_references(&plain, |at| source.at_offset(at), errors);
+        match parse_blocks(text, defined) {
+            Ok(parts) => {
+                narrations.insert(
+                    *id,
+                    Narration {
+                        id: *id,
+                        parts,
+                    },
+                );
+            }
+            Err(e) => errors.push(format!("{}: {}", source.at_offset(e.offset), e.message)),
+        }
+    }
+    narrations
+}
+
+/// How the suit's oxygen runs down, from the game file.
+fn load_oxygen(source: &SourceFile, entry: OxygenEntry, defined: &Defined, errors: &mut Vec<String>) -> Oxygen {
+    let at = source.at(&entry.variable);
+    defined.check_variable(entry.variable.get_ref(), |kind| *kind == VariableType::Int, &at, errors);
+    if *entry.seconds_per_percent.get_ref() <= 0.0 {
+        errors.push(format!("{}: seconds_per_percent has to be more than 0", source.at(&entry.seconds_per_percent)));
+    }
+    check(&defined.narrations, *entry.out.get_ref(), "narration", &source.at(&entry.out), errors);
+    for warning in entry.warning.iter() {
+        defined.check_text(source, &warning.text, errors);
+    }
+    Oxygen {
+        variable: entry.variable.into_inner(),
+        seconds_per_percent: entry.seconds_per_percent.into_inner(),
+        warnings: entry
+            .warning
+            .into_iter()
+            .map(|warning| OxygenWarning {
+                below: warning.below,
+                text: warning.text.into_inner(),
+            })
+            .collect(),
+        out: entry.out.into_inner(),
+    }
+}
+
+/// Locations and their exits.
+fn load_locations(
+    source: &SourceFile,
+    file: LocationsFile,
+    defined: &Defined,
+    errors: &mut Vec<String>,
+) -> HashMap<LocationID, Location> {
+    let mut location_map = HashMap::new();
+    for entry in file.location.into_iter() {
+        check_name(source, &entry.name, errors);
+        check(&defined.narrations, *entry.narration.get_ref(), "narration", &source.at(&entry.narration), errors);
+        let mut exits = Vec::new();
+        for exit in entry.exit.into_iter() {
+            check(&defined.locations, *exit.to.get_ref(), "location", &source.at(&exit.to), errors);
+            if let Some(locked) = exit.locked.as_ref() {
+                defined.check_text(source, locked, errors);
+            }
+            let visible_when = exit.when.as_ref().and_then(|when| defined.condition(source, when, errors));
+            for effect in exit.then.iter() {
+                defined.check_effect(effect.get_ref(), &source.at(effect), errors);
+            }
+            exits.push(Exit {
+                direction: exit.direction.into_inner(),
+                names: exit.names,
+                to: exit.to.into_inner(),
+                locked: exit.locked.map(|locked| locked.into_inner()),
+                visible_when,
+                effects: exit.then.into_iter().map(|effect| effect.into_inner()).collect(),
+            });
+        }
+        let id = entry.id.into_inner();
+        location_map.insert(
+            id,
+            Location {
+                id,
+                name: entry.name.into_inner(),
+                narration_id: entry.narration.into_inner(),
+                exits,
+            },
+        );
+    }
+    location_map
+}
+
+/// Items, where they start and what using them does.
+fn load_items(source: &SourceFile, file: ItemsFile, defined: &Defined, errors: &mut Vec<String>) -> Vec<Item> {
+    let mut item_list = Vec::new();
+    for entry in file.item.into_iter() {
+        check_name(source, &entry.name, errors);
+        if let Some(start) = entry.start.as_ref() {
+            defined.check_placement(start.get_ref(), &source.at(start), errors);
+        }
+        defined.check_text(source, &entry.description, errors);
+        if let Some(usage) = entry.usage.as_ref() {
+            defined.check_text(source, &usage.text, errors);
+            for effect in usage.then.iter() {
+                defined.check_effect(effect.get_ref(), &source.at(effect), errors);
+            }
+        }
+        item_list.push(Item {
+            props_id: entry.id.into_inner(),
+            name: entry.name.into_inner(),
+            description: entry.description.into_inner(),
+            types: entry.types,
+            start: entry.start.map(|start| start.into_inner()).unwrap_or(Placement::Nowhere),
+            names: entry.names,
+            usage: entry.usage.map(|usage| Usage {
+                text: usage.text.into_inner(),
+                effects: usage.then.into_iter().map(|effect| effect.into_inner()).collect(),
+            }),
+        });
+    }
+    item_list
+}
+
+/// Dialogue trees, and the node each person's conversation starts at.
+fn load_dialogues(
+    files: Vec<(SourceFile, DialogueFile)>,
+    defined: &Defined,
+    errors: &mut Vec<String>,
+) -> (HashMap<DialogueNodeID, DialogueNode>, HashMap<DialoguePersonID, DialogueNodeID>) {
+    let mut dialogue_map = HashMap::new();
+    let mut dialogue_roots = HashMap::new();
+    for (source, file) in files.into_iter() {
+        check(&defined.nodes, *file.root.get_ref(), "dialogue node", &source.at(&file.root), errors);
+        if dialogue_roots.insert(file.person, file.root.into_inner()).is_some() {
+            errors.push(format!("{}: {} already has a dialogue", source.path.display(), file.person.name()));
+        }
+
+        for node in file.node.into_iter() {
+            defined.check_text(&source, &node.text, errors);
+            let mut options = Vec::new();
+            for option in node.option.into_iter() {
+                defined.check_text(&source, &option.text, errors);
+                check(&defined.nodes, *option.next.get_ref(), "dialogue node", &source.at(&option.next), errors);
+                let condition = option.when.as_ref().and_then(|when| defined.condition(&source, when, errors));
+                for effect in option.then.iter() {
+                    defined.check_effect(effect.get_ref(), &source.at(effect), errors);
+                }
+                options.push(DialogueOption {
+                    text: option.text.into_inner(),
+                    next: option.next.into_inner(),
+                    condition,
+                    effects: option.then.into_iter().map(|effect| effect.into_inner()).collect(),
+                });
+            }
+            let id = node.id.into_inner();
+            dialogue_map.insert(
+                id,
+                DialogueNode {
+                    id,
+                    speaker: node.speaker,
+                    text: node.text.into_inner(),
+                    options,
+                },
+            );
+        }
+    }
+    (dialogue_map, dialogue_roots)
+}
+
+/// Story triggers, which can start the conversations the dialogue files define.
+fn load_triggers(
+    source: &SourceFile,
+    file: TriggersFile,
+    defined: &Defined,
+    dialogue_roots: &HashMap<DialoguePersonID, DialogueNodeID>,
+    errors: &mut Vec<String>,
+) -> Vec<Trigger> {
+    let mut trigger_list = Vec::new();
+    let mut trigger_ids = HashSet::new();
+    for entry in file.trigger.into_iter() {
+        let at = source.at(&entry.id);
+        if !trigger_ids.insert(entry.id.get_ref().clone()) {
+            errors.push(format!("{}: trigger \"{}\" is defined twice", at, entry.id.get_ref()));
+        }
+        if let Some(enter) = entry.enter.as_ref() {
+            check(&defined.locations, *enter.get_ref(), "location", &source.at(enter), errors);
+        }
+        if let Some(taken) = entry.taken.as_ref() {
+            check(&defined.props, *taken.get_ref(), "item", &source.at(taken), errors);
+        }
+        let mut events = [
+            entry.enter.map(|enter| Event::Enter(enter.into_inner())),
+            entry.turns.map(Event::Turns),
+            entry.seconds.map(Event::Seconds),
+            entry.taken.map(|taken| Event::Taken(taken.into_inner())),
+        ]
+        .into_iter()
+        .flatten();
+        let event = events.next();
+        if events.next().is_some() {
+            errors.push(format!("{}: a trigger can only wait for one of enter, turns, seconds and taken", at));
+        }
+        if event.is_none() && entry.when.is_none() {
+            errors.push(format!("{}: trigger \"{}\" needs an event or a \"when\" condition", at, entry.id.get_ref()));
+        }
+        let condition = entry.when.as_ref().and_then(|when| defined.condition(source, when, errors));
+        if let Some(narration) = entry.narration.as_ref() {
+            check(&defined.narrations, *narration.get_ref(), "narration", &source.at(narration), errors);
+        }
+        if let Some(text) = entry.text.as_ref() {
+            defined.check_text(source, text, errors);
+        }
+        if let Some(person) = entry.talk.filter(|person| !dialogue_roots.contains_key(person)) {
+            errors.push(format!("{}: {} has no dialogue to start", at, person.name()));
+        }
+        for effect in entry.then.iter() {
+            defined.check_effect(effect.get_ref(), &source.at(effect), errors);
+        }
+        trigger_list.push(Trigger {
+            id: entry.id.into_inner(),
+            event,
+            condition,
+            narration: entry.narration.map(|narration| narration.into_inner()),
+            text: entry.text.map(|text| text.into_inner()),
+            talk: entry.talk,
+            effects: entry.then.into_iter().map(|effect| effect.into_inner()).collect(),
+        });
+    }
+    trigger_list
+}
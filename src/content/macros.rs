@@ -0,0 +1,36 @@
+/// An id naming something in the content files. Interned, so it copies and compares as cheaply as an enum.
+#[macro_export]
+macro_rules! content_id {
+    ($name:ident) => {
+        #[derive(Clone, Copy, Eq, Hash, PartialEq, Ord, PartialOrd)]
+        pub struct $name(&'static str);
+
+        impl $name {
+            pub fn new(id: &str) -> Self {
+                $name($crate::content::id::intern(id))
+            }
+
+            pub fn as_str(&self) -> &'static str {
+                self.0
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.0)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <String as serde::Deserialize>::deserialize(deserializer).map(|id| $name::new(&id))
+            }
+        }
+    };
+}
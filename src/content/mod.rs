@@ -0,0 +1,5 @@
+pub mod export;
+pub mod id;
+pub mod lint;
+pub mod loader;
+mod macros;
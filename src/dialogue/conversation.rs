@@ -0,0 +1,88 @@
+use crate::dialogue::logic::{DialogueNodeID, DialogueOption, DialoguePersonID};
+use crate::game::game_state::GameState;
+use crate::printer::{PrintStyle, Printer};
+
+const FAREWELLS: [&str; 4] = ["BYE", "GOODBYE", "END", "LEAVE"];
+
+impl GameState {
+    pub fn in_conversation(&self) -> bool {
+        self.conversation.is_some()
+    }
+
+    /// The options whose condition holds right now, or that have none.
+    pub fn dialogue_options(&self, id: DialogueNodeID) -> Vec<&DialogueOption> {
+        let node = self.dialogues.get(&id).unwrap();
+        node.options
+            .iter()
+            .filter(|option| option.condition.as_ref().is_none_or(|condition| condition.holds(self)))
+            .collect()
+    }
+
+    pub fn start_conversation(&mut self, person: DialoguePersonID, printer: &mut Printer) {
+        match self.dialogue_roots.get(&person) {
+            Some(root) => self.enter_node(*root, printer),
+            None => printer.print(&format!("{} doesn't answer.", person.name()), PrintStyle::Normal),
+        }
+    }
+
+    /// Pick an option in the current conversation, either by its number or by typing its text.
+    pub fn choose_option(&mut self, input: &str, printer: &mut Printer) {
+        let Some(id) = self.conversation else {
+            return;
+        };
+        let input = input.trim();
+        if FAREWELLS.contains(&input.to_uppercase().as_str()) {
+            self.end_conversation(printer);
+            return;
+        }
+
+        let options = self.dialogue_options(id);
+        let chosen = match input.parse::<usize>() {
+            Ok(number) => options.get(number.wrapping_sub(1)).copied(),
+            Err(_) => {
+                let typed = simplify(input);
+                let mut matches = options.iter().filter(|option| simplify(&option.text).starts_with(&typed));
+                match (matches.next(), matches.next()) {
+                    (Some(option), None) if !typed.is_empty() => Some(*option),
+                    _ => None,
+                }
+            }
+        };
+
+        match chosen.map(|option| (option.text.clone(), option.next, option.effects.clone())) {
+            Some((text, next, effects)) => {
+                printer.print(&format!("\"{}\"\n", text), PrintStyle::Normal);
+                for effect in effects.iter() {
+                    effect.apply(self);
+                }
+                self.enter_node(next, printer);
+                self.end_turn(printer);
+            }
+            None => printer.print(
+                &format!("Choose one of the options by number, 1 to {}, or say goodbye.", options.len()),
+                PrintStyle::Normal,
+            ),
+        }
+    }
+
+    fn enter_node(&mut self, id: DialogueNodeID, printer: &mut Printer) {
+        *self.dialogue_visits.entry(id).or_insert(0) += 1;
+        printer.print_dialogue(id, self);
+
+        // Nowhere left to go ends the conversation
+        if self.dialogue_options(id).is_empty() {
+            self.conversation = None;
+        } else {
+            self.conversation = Some(id);
+        }
+    }
+
+    fn end_conversation(&mut self, printer: &mut Printer) {
+        self.conversation = None;
+        printer.print("You end the conversation.", PrintStyle::Normal);
+    }
+}
+
+fn simplify(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric() || c.is_whitespace()).collect::<String>().to_uppercase()
+}
@@ -1,10 +1,14 @@
-use crate::dialogue::dialogue1::dialogue1;
-use std::collections::HashMap;
+use crate::content_id;
+use crate::game::conditions::Condition;
+use crate::game::effects::Effect;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct DialogueOption {
     pub text: String,
     pub next: DialogueNodeID,
+    pub condition: Option<Condition>,
+    pub effects: Vec<Effect>,
 }
 
 #[derive(Debug)]
@@ -15,12 +19,10 @@ pub struct DialogueNode {
     pub options: Vec<DialogueOption>,
 }
 
-#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
-pub enum DialogueNodeID {
-    Dialogue1,
-}
+content_id!(DialogueNodeID);
 
-#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DialoguePersonID {
     Player,
     Central,
@@ -29,9 +31,21 @@ pub enum DialoguePersonID {
     Echo,
 }
 
-pub fn get_dialogues() -> HashMap<DialogueNodeID, DialogueNode> {
-    let mut dialogues = HashMap::new();
-    dialogue1(&mut dialogues);
-    //    println!("{:?}", central_info);
-    dialogues
+impl DialoguePersonID {
+    pub fn name(&self) -> &str {
+        match self {
+            DialoguePersonID::Player => "You",
+            DialoguePersonID::Central => "Central",
+            DialoguePersonID::Watcher => "Watcher",
+            DialoguePersonID::Fixer => "Fixer",
+            DialoguePersonID::Echo => "Echo",
+        }
+    }
+
+    /// The person with this id in the content files, like "central".
+    pub fn find(id: &str) -> Option<DialoguePersonID> {
+        [DialoguePersonID::Central, DialoguePersonID::Watcher, DialoguePersonID::Fixer, DialoguePersonID::Echo]
+            .into_iter()
+            .find(|person| person.name().eq_ignore_ascii_case(id))
+    }
 }
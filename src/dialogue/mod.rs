@@ -1,3 +1,4 @@
-pub mod dialogue1;
+pub mod conversation;
 pub mod logic;
-mod macros;
+pub mod people;
+pub mod person_lookup;
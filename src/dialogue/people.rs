@@ -0,0 +1,128 @@
+use crate::dialogue::logic::DialoguePersonID;
+use crate::game::game_state::GameState;
+use crate::location::locations::LocationID;
+use crate::printer::{PrintStyle, Printer};
+use serde::{Deserialize, Serialize};
+
+/// Where someone is. Central is everywhere, as a voice over the suit radio.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Whereabouts {
+    Everywhere,
+    At(LocationID),
+    Nowhere,
+}
+
+/// Somewhere to go once enough turns have passed.
+#[derive(Debug, Clone)]
+pub struct ScheduledMove {
+    pub turn: u32,
+    pub to: Whereabouts,
+}
+
+/// Someone in the world, as the content defines them.
+#[derive(Debug, Clone)]
+pub struct Person {
+    pub id: DialoguePersonID,
+    pub description: String,
+    pub start: Whereabouts,
+    pub state: String,
+    pub schedule: Vec<ScheduledMove>,
+}
+
+/// What changes about someone as the game goes on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonState {
+    pub whereabouts: Whereabouts,
+    pub state: String,
+    pub following: bool,
+    /// How far through their schedule they are.
+    pub moves: usize,
+}
+
+impl PersonState {
+    pub fn new(person: &Person) -> PersonState {
+        PersonState {
+            whereabouts: person.start,
+            state: person.state.clone(),
+            following: false,
+            moves: 0,
+        }
+    }
+}
+
+impl GameState {
+    /// Whether the player can talk to someone: in the same place, or everywhere.
+    pub fn can_reach(&self, id: DialoguePersonID) -> bool {
+        match self.person_states.get(&id).map(|person| person.whereabouts) {
+            Some(Whereabouts::Everywhere) => true,
+            Some(Whereabouts::At(location)) => location == self.location,
+            Some(Whereabouts::Nowhere) | None => false,
+        }
+    }
+
+    /// Whether someone is standing right here, close enough to hand things to.
+    pub fn is_present(&self, id: DialoguePersonID) -> bool {
+        self.person_states.get(&id).is_some_and(|person| person.whereabouts == Whereabouts::At(self.location))
+    }
+
+    /// Everyone standing at a location, in a stable order for listing.
+    pub fn people_at(&self, id: LocationID) -> Vec<DialoguePersonID> {
+        let mut people = self
+            .person_states
+            .iter()
+            .filter(|(_, person)| person.whereabouts == Whereabouts::At(id))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        people.sort_by_key(|id| id.name().to_string());
+        people
+    }
+
+    pub fn place_person(&mut self, id: DialoguePersonID, whereabouts: Whereabouts) {
+        if let Some(person) = self.person_states.get_mut(&id) {
+            person.whereabouts = whereabouts;
+        }
+    }
+
+    pub fn set_following(&mut self, id: DialoguePersonID, following: bool) {
+        if let Some(person) = self.person_states.get_mut(&id) {
+            person.following = following;
+        }
+    }
+
+    /// Anyone following the player from where they were comes along.
+    pub fn bring_followers(&mut self, from: LocationID, to: LocationID) {
+        for person in self.person_states.values_mut() {
+            if person.following && person.whereabouts == Whereabouts::At(from) {
+                person.whereabouts = Whereabouts::At(to);
+            }
+        }
+    }
+
+    /// Move people along their schedules. Nobody walks off mid-conversation or while following the player,
+    /// the move waits until then.
+    pub fn move_people(&mut self, printer: &mut Printer) {
+        if self.in_conversation() || self.game_over {
+            return;
+        }
+        let mut moved = Vec::new();
+        for (id, person) in self.person_states.iter_mut().filter(|(_, person)| !person.following) {
+            let schedule = &self.people.get(id).unwrap().schedule;
+            while let Some(next) = schedule.get(person.moves).filter(|next| next.turn <= self.turns) {
+                moved.push((*id, person.whereabouts, next.to));
+                person.whereabouts = next.to;
+                person.moves += 1;
+            }
+        }
+
+        moved.sort_by_key(|(id, _, _)| id.name().to_string());
+        let here = Whereabouts::At(self.location);
+        for (id, from, to) in moved {
+            if from == here && to != here {
+                printer.print(&format!("\n{} leaves.", id.name()), PrintStyle::Normal);
+            } else if to == here && from != here {
+                printer.print(&format!("\n{} arrives.", id.name()), PrintStyle::Normal);
+            }
+        }
+    }
+}
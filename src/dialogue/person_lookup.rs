@@ -0,0 +1,42 @@
+use crate::dialogue::logic::DialoguePersonID;
+use crate::game::game_state::GameState;
+use std::collections::HashMap;
+
+pub struct PersonLookup {
+    lookups: HashMap<String, DialoguePersonID>,
+}
+
+impl Default for PersonLookup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PersonLookup {
+    pub fn new() -> PersonLookup {
+        let mut lookups = HashMap::new();
+        lookups.insert("CENTRAL".to_string(), DialoguePersonID::Central);
+        lookups.insert("AI".to_string(), DialoguePersonID::Central);
+        lookups.insert("COMPUTER".to_string(), DialoguePersonID::Central);
+
+        lookups.insert("WATCHER".to_string(), DialoguePersonID::Watcher);
+
+        lookups.insert("FIXER".to_string(), DialoguePersonID::Fixer);
+        lookups.insert("DRONE".to_string(), DialoguePersonID::Fixer);
+
+        lookups.insert("ECHO".to_string(), DialoguePersonID::Echo);
+
+        PersonLookup {
+            lookups,
+        }
+    }
+
+    pub fn find_person(&self, key: &str) -> Option<&DialoguePersonID> {
+        self.lookups.get(&key.to_uppercase())
+    }
+
+    /// The names of everyone who can be talked to from here, so people elsewhere don't give themselves away.
+    pub fn words(&self, game_state: &GameState) -> Vec<&str> {
+        self.lookups.iter().filter(|(_, id)| game_state.can_reach(**id)).map(|(name, _)| name.as_str()).collect()
+    }
+}
@@ -0,0 +1,139 @@
+use crate::dialogue::logic::{DialogueNodeID, DialoguePersonID};
+use crate::game::game_state::GameState;
+use crate::game::variables::Value;
+use crate::location::locations::LocationID;
+use crate::props::enums::PropsID;
+
+/// A test on the game state, written in the content as an expression, see expression.rs.
+#[derive(Debug, Clone)]
+pub enum Condition {
+    Flag(String),
+    HasItem(PropsID),
+    ItemHere(PropsID),
+    At(LocationID),
+    VisitedLocation(LocationID),
+    VisitedNode(DialogueNodeID),
+    Present(DialoguePersonID),
+    Compare(Operand, Comparison, Operand),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+/// One side of a comparison.
+#[derive(Debug, Clone)]
+pub enum Operand {
+    Variable(String),
+    LocationVisits(LocationID),
+    NodeVisits(DialogueNodeID),
+    PersonState(DialoguePersonID),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+impl Operand {
+    fn value(&self, game_state: &GameState) -> Value {
+        match self {
+            Operand::Variable(name) => game_state.variables.get(name).cloned().unwrap_or(Value::Int(0)),
+            Operand::LocationVisits(id) => Value::Int(*game_state.visits.get(id).unwrap_or(&0) as i64),
+            Operand::NodeVisits(id) => Value::Int(*game_state.dialogue_visits.get(id).unwrap_or(&0) as i64),
+            Operand::PersonState(id) => {
+                Value::Text(game_state.person_states.get(id).map(|person| person.state.clone()).unwrap_or_default())
+            }
+            Operand::Literal(value) => value.clone(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Operand::Variable(name) => name.clone(),
+            Operand::LocationVisits(id) => format!("visits({:?})", id),
+            Operand::NodeVisits(id) => format!("visits({:?})", id),
+            Operand::PersonState(id) => format!("state({})", id.name().to_lowercase()),
+            Operand::Literal(Value::Text(text)) => format!("\"{}\"", text),
+            Operand::Literal(value) => value.to_string(),
+        }
+    }
+}
+
+impl Condition {
+    pub fn holds(&self, game_state: &GameState) -> bool {
+        match self {
+            Condition::Flag(flag) => game_state.variables.is_true(flag),
+            Condition::HasItem(id) => game_state.items.is_carried(*id),
+            Condition::ItemHere(id) => game_state.items.is_visible(*id, game_state.location),
+            Condition::At(id) => game_state.location == *id,
+            Condition::VisitedLocation(id) => game_state.visits.contains_key(id),
+            Condition::VisitedNode(id) => game_state.dialogue_visits.contains_key(id),
+            Condition::Present(id) => game_state.is_present(*id),
+            Condition::Compare(left, comparison, right) => {
+                let (left, right) = (left.value(game_state), right.value(game_state));
+                match (comparison, &left, &right) {
+                    (Comparison::Equal, _, _) => left == right,
+                    (Comparison::NotEqual, _, _) => left != right,
+                    (Comparison::Less, Value::Int(left), Value::Int(right)) => left < right,
+                    (Comparison::LessOrEqual, Value::Int(left), Value::Int(right)) => left <= right,
+                    (Comparison::Greater, Value::Int(left), Value::Int(right)) => left > right,
+                    (Comparison::GreaterOrEqual, Value::Int(left), Value::Int(right)) => left >= right,
+                    // Only numbers are ordered, the loader doesn't let anything else through
+                    _ => false,
+                }
+            }
+            Condition::Not(condition) => !condition.holds(game_state),
+            Condition::And(left, right) => left.holds(game_state) && right.holds(game_state),
+            Condition::Or(left, right) => left.holds(game_state) || right.holds(game_state),
+        }
+    }
+
+    /// Back to an expression for tools, e.g. !lab_unlocked && has(access_card).
+    pub fn describe(&self) -> String {
+        match self {
+            Condition::Flag(flag) => flag.clone(),
+            Condition::HasItem(id) => format!("has({:?})", id),
+            Condition::ItemHere(id) => format!("here({:?})", id),
+            Condition::At(id) => format!("at({:?})", id),
+            Condition::VisitedLocation(id) => format!("visited({:?})", id),
+            Condition::VisitedNode(id) => format!("visited({:?})", id),
+            Condition::Present(id) => format!("present({})", id.name().to_lowercase()),
+            Condition::Compare(left, comparison, right) => {
+                format!("{} {} {}", left.describe(), comparison.symbol(), right.describe())
+            }
+            Condition::Not(condition) => match condition.as_ref() {
+                Condition::Compare(..) | Condition::And(..) | Condition::Or(..) => {
+                    format!("!({})", condition.describe())
+                }
+                _ => format!("!{}", condition.describe()),
+            },
+            Condition::And(left, right) => {
+                let side = |condition: &Condition| match condition {
+                    Condition::Or(..) => format!("({})", condition.describe()),
+                    _ => condition.describe(),
+                };
+                format!("{} && {}", side(left), side(right))
+            }
+            Condition::Or(left, right) => format!("{} || {}", left.describe(), right.describe()),
+        }
+    }
+}
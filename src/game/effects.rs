@@ -0,0 +1,49 @@
+use crate::dialogue::logic::DialoguePersonID;
+use crate::dialogue::people::Whereabouts;
+use crate::game::game_state::GameState;
+use crate::game::variables::Value;
+use crate::location::direction::Direction;
+use crate::location::locations::LocationID;
+use crate::props::enums::PropsID;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    SetFlag(String),
+    ClearFlag(String),
+    Set(String, Value),
+    Add(String, i64),
+    GiveItem(PropsID),
+    TakeItem(PropsID),
+    UnlockExit(LocationID, Direction),
+    SetState(DialoguePersonID, String),
+    Follow(DialoguePersonID),
+    Stay(DialoguePersonID),
+    MovePerson(DialoguePersonID, LocationID),
+}
+
+impl Effect {
+    pub fn apply(&self, game_state: &mut GameState) {
+        match self {
+            Effect::SetFlag(flag) => game_state.set_variable(flag, Value::Bool(true)),
+            Effect::ClearFlag(flag) => game_state.set_variable(flag, Value::Bool(false)),
+            Effect::Set(name, value) => game_state.set_variable(name, value.clone()),
+            Effect::Add(name, amount) => {
+                let value = game_state.variables.int(name).saturating_add(*amount);
+                game_state.set_variable(name, Value::Int(value));
+            }
+            Effect::GiveItem(id) => game_state.items.give_player(*id),
+            Effect::TakeItem(id) => game_state.items.take_from_player(*id),
+            Effect::UnlockExit(id, direction) => game_state.unlock_exit(*id, *direction),
+            Effect::SetState(id, state) => {
+                if let Some(person) = game_state.person_states.get_mut(id) {
+                    person.state = state.clone();
+                }
+            }
+            Effect::Follow(id) => game_state.set_following(*id, true),
+            Effect::Stay(id) => game_state.set_following(*id, false),
+            Effect::MovePerson(id, location) => game_state.place_person(*id, Whereabouts::At(*location)),
+        }
+    }
+}
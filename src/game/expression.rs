@@ -0,0 +1,322 @@
+use crate::dialogue::logic::{DialogueNodeID, DialoguePersonID};
+use crate::game::conditions::{Comparison, Condition, Operand};
+use crate::game::variables::{Value, VariableType};
+use crate::location::locations::LocationID;
+use crate::props::enums::PropsID;
+
+/// What a condition can refer to, so mistakes are found when the content is loaded.
+pub trait Names {
+    fn is_location(&self, id: LocationID) -> bool;
+    fn is_item(&self, id: PropsID) -> bool;
+    fn is_node(&self, id: DialogueNodeID) -> bool;
+    fn variable(&self, name: &str) -> Option<&VariableType>;
+    /// The states a person can be in, as an enum.
+    fn person(&self, id: DialoguePersonID) -> Option<&VariableType>;
+}
+
+/// What's wrong with a condition, and the byte offset into it where it went wrong.
+#[derive(Debug, PartialEq)]
+pub struct ExpressionError {
+    pub offset: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Int(i64),
+    Text(String),
+    Open,
+    Close,
+    Not,
+    And,
+    Or,
+    Compare(Comparison),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Name(name) => name.clone(),
+            Token::Int(value) => value.to_string(),
+            Token::Text(text) => format!("\"{}\"", text),
+            Token::Open => "(".to_string(),
+            Token::Close => ")".to_string(),
+            Token::Not => "!".to_string(),
+            Token::And => "&&".to_string(),
+            Token::Or => "||".to_string(),
+            Token::Compare(comparison) => comparison.symbol().to_string(),
+        }
+    }
+}
+
+fn error<T>(offset: usize, message: String) -> Result<T, ExpressionError> {
+    Err(ExpressionError {
+        offset,
+        message,
+    })
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut followed_by = |expected: char| chars.next_if(|(_, c)| *c == expected).is_some();
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' if followed_by('&') => Token::And,
+            '|' if followed_by('|') => Token::Or,
+            '=' if followed_by('=') => Token::Compare(Comparison::Equal),
+            '=' => return error(start, "use == to compare".to_string()),
+            '!' if followed_by('=') => Token::Compare(Comparison::NotEqual),
+            '!' => Token::Not,
+            '<' if followed_by('=') => Token::Compare(Comparison::LessOrEqual),
+            '<' => Token::Compare(Comparison::Less),
+            '>' if followed_by('=') => Token::Compare(Comparison::GreaterOrEqual),
+            '>' => Token::Compare(Comparison::Greater),
+            '"' => match text[start + 1..].find('"') {
+                Some(end) => {
+                    let value = &text[start + 1..start + 1 + end];
+                    while chars.next_if(|(at, _)| *at <= start + 1 + end).is_some() {}
+                    Token::Text(value.to_string())
+                }
+                None => return error(start, "the quote is never closed".to_string()),
+            },
+            _ if c.is_ascii_digit() || c == '-' => {
+                let mut end = start + 1;
+                while let Some((at, _)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    end = at + 1;
+                }
+                match text[start..end].parse() {
+                    Ok(value) => Token::Int(value),
+                    Err(_) => return error(start, format!("\"{}\" isn't a number", &text[start..end])),
+                }
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((at, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                    end = at + c.len_utf8();
+                }
+                Token::Name(text[start..end].to_string())
+            }
+            _ => return error(start, format!("unexpected \"{}\"", c)),
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Either something that's already true or false, or a value still to be compared.
+enum Term {
+    Test(Condition),
+    Value(Operand, VariableType),
+}
+
+struct ExpressionParser<'a, N: Names> {
+    names: &'a N,
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    length: usize,
+}
+
+impl<N: Names> ExpressionParser<'_, N> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.position).map(|(offset, _)| *offset).unwrap_or(self.length)
+    }
+
+    fn take(&mut self) -> Result<(usize, Token), ExpressionError> {
+        match self.tokens.get(self.position) {
+            Some(token) => {
+                self.position += 1;
+                Ok(token.clone())
+            }
+            None => error(self.length, "the condition ends too soon".to_string()),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExpressionError> {
+        match self.take()? {
+            (_, token) if token == expected => Ok(()),
+            (offset, token) => {
+                error(offset, format!("expected \"{}\", found \"{}\"", expected.describe(), token.describe()))
+            }
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, ExpressionError> {
+        let mut condition = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, ExpressionError> {
+        let mut condition = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, ExpressionError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Condition::Not(Box::new(self.not()?)))
+            }
+            Some(Token::Open) => {
+                self.position += 1;
+                let condition = self.or()?;
+                self.expect(Token::Close)?;
+                Ok(condition)
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Condition, ExpressionError> {
+        let offset = self.offset();
+        let left = self.term()?;
+        let Some(&Token::Compare(comparison)) = self.peek() else {
+            return match left {
+                Term::Test(condition) => Ok(condition),
+                Term::Value(Operand::Variable(name), VariableType::Bool) => Ok(Condition::Flag(name)),
+                Term::Value(Operand::Variable(name), kind) => {
+                    error(offset, format!("variable \"{}\" is {}, compare it with something", name, kind.name()))
+                }
+                Term::Value(operand, _) => error(offset, format!("{} isn't a condition", operand.describe())),
+            };
+        };
+        self.position += 1;
+        let right_offset = self.offset();
+        let right = self.term()?;
+        let (left, left_kind) = value(left, offset)?;
+        let (right, right_kind) = value(right, right_offset)?;
+        check_comparison(comparison, (&left, left_kind), (&right, right_kind), offset)?;
+        Ok(Condition::Compare(left, comparison, right))
+    }
+
+    fn term(&mut self) -> Result<Term, ExpressionError> {
+        let (offset, token) = self.take()?;
+        match token {
+            Token::Int(value) => Ok(Term::Value(Operand::Literal(Value::Int(value)), VariableType::Int)),
+            Token::Text(text) => Ok(Term::Value(Operand::Literal(Value::Text(text)), VariableType::String)),
+            Token::Name(name) if name == "true" || name == "false" => {
+                Ok(Term::Value(Operand::Literal(Value::Bool(name == "true")), VariableType::Bool))
+            }
+            Token::Name(name) if self.peek() == Some(&Token::Open) => self.call(&name, offset),
+            Token::Name(name) => match self.names.variable(&name) {
+                Some(kind) => Ok(Term::Value(Operand::Variable(name), kind.clone())),
+                None => error(offset, format!("unknown variable \"{}\"", name)),
+            },
+            token => error(offset, format!("expected a condition, found \"{}\"", token.describe())),
+        }
+    }
+
+    /// has(item), here(item), at(location), visited(location or node), visits(location or node),
+    /// present(person) and state(person).
+    fn call(&mut self, function: &str, offset: usize) -> Result<Term, ExpressionError> {
+        self.expect(Token::Open)?;
+        let (argument_offset, argument) = match self.take()? {
+            (offset, Token::Name(argument)) => (offset, argument),
+            (offset, token) => return error(offset, format!("expected a name, found \"{}\"", token.describe())),
+        };
+        self.expect(Token::Close)?;
+
+        let names = self.names;
+        let item = || {
+            let id = PropsID::new(&argument);
+            if names.is_item(id) {
+                Ok(id)
+            } else {
+                error(argument_offset, format!("unknown item \"{}\"", argument))
+            }
+        };
+        let person = DialoguePersonID::find(&argument).and_then(|id| names.person(id).map(|states| (id, states)));
+        let location = LocationID::new(&argument);
+        let node = DialogueNodeID::new(&argument);
+        match function {
+            "has" => Ok(Term::Test(Condition::HasItem(item()?))),
+            "here" => Ok(Term::Test(Condition::ItemHere(item()?))),
+            "at" if names.is_location(location) => Ok(Term::Test(Condition::At(location))),
+            "at" => error(argument_offset, format!("unknown location \"{}\"", argument)),
+            "visited" if names.is_location(location) => Ok(Term::Test(Condition::VisitedLocation(location))),
+            "visited" if names.is_node(node) => Ok(Term::Test(Condition::VisitedNode(node))),
+            "visits" if names.is_location(location) => {
+                Ok(Term::Value(Operand::LocationVisits(location), VariableType::Int))
+            }
+            "visits" if names.is_node(node) => Ok(Term::Value(Operand::NodeVisits(node), VariableType::Int)),
+            "present" | "state" => match person {
+                Some((id, _)) if function == "present" => Ok(Term::Test(Condition::Present(id))),
+                Some((id, states)) => Ok(Term::Value(Operand::PersonState(id), states.clone())),
+                None => error(argument_offset, format!("unknown person \"{}\"", argument)),
+            },
+            "visited" | "visits" => {
+                error(argument_offset, format!("unknown location or dialogue node \"{}\"", argument))
+            }
+            _ => error(offset, format!("unknown function \"{}\"", function)),
+        }
+    }
+}
+
+fn value(term: Term, offset: usize) -> Result<(Operand, VariableType), ExpressionError> {
+    match term {
+        Term::Value(operand, kind) => Ok((operand, kind)),
+        Term::Test(condition) => {
+            error(offset, format!("{} is true or false, it can't be compared", condition.describe()))
+        }
+    }
+}
+
+/// Only numbers are ordered. Anything can be equal to something of the same type, and an enum
+/// can only equal one of its own values.
+fn check_comparison(
+    comparison: Comparison,
+    (left, left_kind): (&Operand, VariableType),
+    (right, right_kind): (&Operand, VariableType),
+    offset: usize,
+) -> Result<(), ExpressionError> {
+    let ordered = !matches!(comparison, Comparison::Equal | Comparison::NotEqual);
+    if ordered && (left_kind != VariableType::Int || right_kind != VariableType::Int) {
+        return error(offset, format!("\"{}\" only compares numbers", comparison.symbol()));
+    }
+    for (kind, other) in [(&left_kind, right), (&right_kind, left)] {
+        if let (VariableType::Enum(values), Operand::Literal(Value::Text(text))) = (kind, other) {
+            if !values.contains(text) {
+                return error(offset, format!("\"{}\" isn't one of {}", text, values.join(", ")));
+            }
+        }
+    }
+    let textual = |kind: &VariableType| matches!(kind, VariableType::String | VariableType::Enum(_));
+    if left_kind != right_kind && !(textual(&left_kind) && textual(&right_kind)) {
+        return error(offset, format!("can't compare {} with {}", left_kind.name(), right_kind.name()));
+    }
+    Ok(())
+}
+
+/// Read a condition like has(helmet) && oxygen_percent < 20 && !visited(central_diagnostics).
+pub fn parse_condition(text: &str, names: &impl Names) -> Result<Condition, ExpressionError> {
+    let mut parser = ExpressionParser {
+        names,
+        tokens: tokenize(text)?,
+        position: 0,
+        length: text.len(),
+    };
+    if parser.tokens.is_empty() {
+        return error(0, "the condition is empty".to_string());
+    }
+    let condition = parser.or()?;
+    match parser.tokens.get(parser.position) {
+        Some((offset, token)) => error(*offset, format!("unexpected \"{}\"", token.describe())),
+        None => Ok(condition),
+    }
+}
@@ -1,28 +1,133 @@
-use crate::dialogue::logic::{get_dialogues, DialogueNode, DialogueNodeID};
-use crate::location::locations::{get_locations, Location, LocationID};
-use crate::narration::narrations::{get_narrations, Narration, NarrationID};
+use crate::content::loader::Content;
+use crate::dialogue::logic::{DialogueNode, DialogueNodeID, DialoguePersonID};
+use crate::dialogue::people::{Person, PersonState};
+use crate::game::oxygen::{Difficulty, Oxygen};
+use crate::game::save::SAVE_FILE;
+use crate::game::triggers::Trigger;
+use crate::game::variables::{Value, Variables};
+use crate::location::direction::Direction;
+use crate::location::exit::Exit;
+use crate::location::locations::{Location, LocationID};
+use crate::narration::narrations::{Narration, NarrationID};
 use crate::printer::Printer;
 use crate::props::items::Items;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 pub struct GameState {
     pub dialogues: HashMap<DialogueNodeID, DialogueNode>,
+    pub dialogue_roots: HashMap<DialoguePersonID, DialogueNodeID>,
     pub narrations: HashMap<NarrationID, Narration>,
     pub locations: HashMap<LocationID, Location>,
     pub items: Items,
+    pub start_narration: NarrationID,
+    pub location: LocationID,
+    pub visits: HashMap<LocationID, u32>,
+    pub variables: Variables,
+    pub locked_exits: HashSet<(LocationID, Direction)>,
+    pub conversation: Option<DialogueNodeID>,
+    pub dialogue_visits: HashMap<DialogueNodeID, u32>,
+    pub oxygen: Option<Oxygen>,
+    pub difficulty: Difficulty,
+    pub turns: u32,
+    /// Seconds towards the next percent of oxygen on hard difficulty.
+    pub breathing: f32,
+    pub game_over: bool,
+    pub triggers: Vec<Trigger>,
+    pub fired: HashSet<String>,
+    /// Seconds of play so far, for triggers on a timer.
+    pub playing: f32,
+    /// Where SAVE and RESTORE keep the game.
+    pub save_path: PathBuf,
+    pub people: HashMap<DialoguePersonID, Person>,
+    pub person_states: HashMap<DialoguePersonID, PersonState>,
 }
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new(content: Content) -> Self {
+        let locked_exits = content
+            .locations
+            .values()
+            .flat_map(|location| {
+                location.exits.iter().filter(|exit| exit.locked.is_some()).map(|exit| (location.id, exit.direction))
+            })
+            .collect();
+
         GameState {
-            dialogues: get_dialogues(),
-            narrations: get_narrations(),
-            locations: get_locations(),
-            items: Items::new(),
+            dialogues: content.dialogues,
+            dialogue_roots: content.dialogue_roots,
+            narrations: content.narrations,
+            locations: content.locations,
+            items: Items::new(content.items),
+            start_narration: content.start_narration,
+            location: content.start_location,
+            visits: HashMap::new(),
+            variables: Variables::new(content.variables),
+            locked_exits,
+            conversation: None,
+            dialogue_visits: HashMap::new(),
+            oxygen: content.oxygen,
+            difficulty: Difficulty::Normal,
+            turns: 0,
+            breathing: 0.0,
+            game_over: false,
+            triggers: content.triggers,
+            fired: HashSet::new(),
+            playing: 0.0,
+            save_path: PathBuf::from(SAVE_FILE),
+            person_states: content.people.iter().map(|person| (person.id, PersonState::new(person))).collect(),
+            people: content.people.into_iter().map(|person| (person.id, person)).collect(),
         }
     }
 
-    pub fn start(&self, printer: &mut Printer) {
-        printer.print_location(LocationID::InitialLocation, &self);
+    pub fn start(&mut self, printer: &mut Printer) {
+        printer.print_narration(self.start_narration, self);
+        self.visits.insert(self.location, 1);
+    }
+
+    pub fn visible_exits(&self, id: LocationID) -> Vec<&Exit> {
+        let location = self.locations.get(&id).unwrap();
+        location
+            .exits
+            .iter()
+            .filter(|exit| exit.visible_when.as_ref().is_none_or(|condition| condition.holds(self)))
+            .collect()
+    }
+
+    /// Names of the things the player can see or is carrying, and of the ways out.
+    pub fn visible_names(&self) -> Vec<&str> {
+        let items = self
+            .items
+            .placements()
+            .into_iter()
+            .filter(|(id, _)| self.items.is_visible(*id, self.location))
+            .flat_map(|(id, _)| self.items.get(id).names.iter());
+        let exits = self.visible_exits(self.location).into_iter().flat_map(|exit| exit.names.iter());
+        items.chain(exits).map(String::as_str).collect()
+    }
+
+    /// Content is checked when it's loaded, so a bad name or type here is a mistake in the code.
+    pub fn set_variable(&mut self, name: &str, value: Value) {
+        let result = self.variables.set(name, value);
+        debug_assert!(result.is_ok(), "can't set variable: {:?}", result);
+    }
+
+    pub fn find_exit(&self, key: &str) -> Option<Direction> {
+        self.visible_exits(self.location).iter().find(|exit| exit.matches(key)).map(|exit| exit.direction)
+    }
+
+    pub fn is_locked(&self, id: LocationID, direction: Direction) -> bool {
+        self.locked_exits.contains(&(id, direction))
+    }
+
+    pub fn unlock_exit(&mut self, id: LocationID, direction: Direction) {
+        self.locked_exits.remove(&(id, direction));
+    }
+
+    pub fn move_to(&mut self, id: LocationID, printer: &mut Printer) {
+        self.bring_followers(self.location, id);
+        self.location = id;
+        *self.visits.entry(id).or_insert(0) += 1;
+        printer.print_location(id, self);
     }
 }
@@ -0,0 +1,286 @@
+use crate::game::game_state::GameState;
+use crate::location::direction::Direction;
+use crate::markup::escape;
+use crate::parser::command::{Command, Noun};
+use crate::parser::enums::Verbs;
+use crate::printer::{PrintStyle, Printer};
+use crate::props::enums::{PropTypes, PropsID};
+use crate::props::placement::Placement;
+
+impl GameState {
+    pub fn handle(&mut self, command: &Command, printer: &mut Printer) {
+        // Props have to be at hand before anything can be done with them
+        for noun in [command.object, command.indirect].into_iter().flatten() {
+            if let Noun::Prop(id) = noun {
+                if !self.items.is_visible(id, self.location) {
+                    printer.print(&format!("You can't see the {} here.", self.items.get(id).name), PrintStyle::Normal);
+                    return;
+                }
+            }
+        }
+
+        match command.verb {
+            Verbs::Close => self.handle_close(command, printer),
+            Verbs::Drop => self.handle_drop(command, printer),
+            Verbs::Give => self.handle_give(command, printer),
+            Verbs::Inventory => self.handle_inventory(printer),
+            Verbs::Look => self.handle_look(command, printer),
+            Verbs::Go => self.handle_go(command, printer),
+            Verbs::Open => self.handle_open(command, printer),
+            Verbs::Restore => self.handle_restore(printer),
+            Verbs::Run => self.handle_run(command, printer),
+            Verbs::Save => self.handle_save(printer),
+            Verbs::Take => self.handle_take(command, printer),
+            Verbs::Talk => self.handle_talk(command, printer),
+            Verbs::Use => self.handle_use(command, printer),
+        }
+
+        // Saving and restoring happen outside the story, they don't take a turn
+        if !matches!(command.verb, Verbs::Save | Verbs::Restore) {
+            self.end_turn(printer);
+        }
+    }
+
+    fn noun_name(&self, noun: Noun) -> String {
+        match noun {
+            Noun::Prop(id) => format!("the {}", self.items.get(id).name),
+            Noun::Person(id) => id.name().to_string(),
+            Noun::Exit(direction) => format!("the way {}", direction.name()),
+        }
+    }
+
+    fn handle_close(&mut self, command: &Command, printer: &mut Printer) {
+        match command.object {
+            Some(noun) => printer.print(&format!("You can't close {}.", self.noun_name(noun)), PrintStyle::Normal),
+            None => printer.print("What do you want to close?", PrintStyle::Normal),
+        }
+    }
+
+    fn handle_drop(&mut self, command: &Command, printer: &mut Printer) {
+        match (command.object, command.indirect) {
+            (Some(Noun::Prop(id)), _) if !self.items.is_carried(id) => {
+                printer.print(&format!("You aren't carrying the {}.", self.items.get(id).name), PrintStyle::Normal)
+            }
+            (Some(Noun::Prop(id)), _) if self.items.get(id).is(PropTypes::Fixed) => {
+                printer.print(&format!("The {} is part of your suit.", self.items.get(id).name), PrintStyle::Normal)
+            }
+            (Some(Noun::Prop(id)), Some(Noun::Prop(container))) if self.items.is_around(id, container) => printer
+                .print(&format!("You can't put the {} inside itself.", self.items.get(id).name), PrintStyle::Normal),
+            (Some(Noun::Prop(id)), Some(Noun::Prop(container)))
+                if self.items.get(container).is(PropTypes::Container) =>
+            {
+                self.items.place(id, Placement::Inside(container));
+                printer.print(
+                    &format!("You put the {} in the {}.", self.items.get(id).name, self.items.get(container).name),
+                    PrintStyle::Normal,
+                );
+            }
+            (Some(Noun::Prop(_)), Some(target)) => {
+                printer.print(&format!("You can't put anything in {}.", self.noun_name(target)), PrintStyle::Normal)
+            }
+            (Some(Noun::Prop(id)), None) => {
+                self.items.place(id, Placement::Location(self.location));
+                printer.print(&format!("You drop the {}.", self.items.get(id).name), PrintStyle::Normal);
+            }
+            (Some(noun), _) => printer.print(&format!("You can't drop {}.", self.noun_name(noun)), PrintStyle::Normal),
+            (None, _) => printer.print("What do you want to drop?", PrintStyle::Normal),
+        }
+    }
+
+    fn handle_give(&mut self, command: &Command, printer: &mut Printer) {
+        match (command.object, command.indirect) {
+            (Some(Noun::Prop(id)), _) if !self.items.is_carried(id) => {
+                printer.print(&format!("You aren't carrying the {}.", self.items.get(id).name), PrintStyle::Normal)
+            }
+            (Some(Noun::Prop(id)), _) if self.items.get(id).is(PropTypes::Fixed) => {
+                printer.print(&format!("The {} is part of your suit.", self.items.get(id).name), PrintStyle::Normal)
+            }
+            (Some(Noun::Prop(_)), Some(Noun::Person(person))) if !self.is_present(person) => {
+                printer.print(&format!("{} isn't here to take it.", person.name()), PrintStyle::Normal)
+            }
+            (Some(Noun::Prop(id)), Some(Noun::Person(person))) => {
+                self.items.place(id, Placement::Person(person));
+                printer.print(
+                    &format!("You give the {} to {}.", self.items.get(id).name, person.name()),
+                    PrintStyle::Normal,
+                );
+            }
+            (Some(_), Some(target)) => {
+                printer.print(&format!("You can't give anything to {}.", self.noun_name(target)), PrintStyle::Normal)
+            }
+            (Some(noun), None) => {
+                printer.print(&format!("Who do you want to give {} to?", self.noun_name(noun)), PrintStyle::Normal)
+            }
+            (None, _) => printer.print("What do you want to give?", PrintStyle::Normal),
+        }
+    }
+
+    fn handle_inventory(&mut self, printer: &mut Printer) {
+        let carried = self.items.carried();
+        if carried.is_empty() {
+            printer.print("You aren't carrying anything.", PrintStyle::Normal);
+            return;
+        }
+        let lines = carried.iter().map(|id| format!("the {}", self.describe_item(*id))).collect::<Vec<String>>();
+        printer.print(&format!("You are carrying:<br>{}", lines.join("<br>")), PrintStyle::Normal);
+    }
+
+    /// An item's name, followed by anything inside it.
+    fn describe_item(&self, id: PropsID) -> String {
+        let contents = self.items.inside(id);
+        if contents.is_empty() {
+            return self.items.get(id).name.clone();
+        }
+        let contents =
+            contents.iter().map(|inside| format!("the {}", self.describe_item(*inside))).collect::<Vec<String>>();
+        format!("{} (holding {})", self.items.get(id).name, contents.join(", "))
+    }
+
+    fn handle_look(&mut self, command: &Command, printer: &mut Printer) {
+        match command.object {
+            Some(Noun::Prop(id)) => {
+                printer.print(&self.variables.interpolate(&self.items.get(id).description), PrintStyle::Normal);
+                let contents = self.items.inside(id);
+                if !contents.is_empty() {
+                    let contents = contents
+                        .iter()
+                        .map(|inside| format!("the {}", self.describe_item(*inside)))
+                        .collect::<Vec<String>>();
+                    printer.print(&format!("Inside is {}.", contents.join(", ")), PrintStyle::Normal);
+                }
+            }
+            Some(Noun::Person(person)) if self.can_reach(person) => {
+                let description = self.people.get(&person).unwrap().description.clone();
+                printer.print(&self.variables.interpolate(&description), PrintStyle::Normal)
+            }
+            Some(Noun::Person(person)) => {
+                printer.print(&format!("You can't see {} anywhere.", person.name()), PrintStyle::Normal)
+            }
+            Some(Noun::Exit(direction)) => {
+                match self.visible_exits(self.location).iter().find(|exit| exit.direction == direction) {
+                    Some(exit) => printer.print(
+                        &format!(
+                            "The way {} leads to the {}.",
+                            direction.name(),
+                            self.locations.get(&exit.to).unwrap().name.to_lowercase()
+                        ),
+                        PrintStyle::Normal,
+                    ),
+                    None => {
+                        printer.print(&format!("There is nothing to the {}.", direction.name()), PrintStyle::Normal)
+                    }
+                }
+            }
+            None => printer.print_location(self.location, self),
+        }
+    }
+
+    fn handle_go(&mut self, command: &Command, printer: &mut Printer) {
+        match command.object {
+            Some(Noun::Exit(direction)) => self.go(direction, printer),
+            Some(noun) => printer.print(&format!("You can't go to {}.", self.noun_name(noun)), PrintStyle::Normal),
+            None => printer.print("Where do you want to go?", PrintStyle::Normal),
+        }
+    }
+
+    fn go(&mut self, direction: Direction, printer: &mut Printer) {
+        let exit = self.visible_exits(self.location).into_iter().find(|exit| exit.direction == direction);
+        match exit {
+            Some(exit) if self.is_locked(self.location, direction) => printer.print(
+                &self.variables.interpolate(exit.locked.as_deref().unwrap_or("The way is locked.")),
+                PrintStyle::Normal,
+            ),
+            Some(exit) => {
+                let (to, effects) = (exit.to, exit.effects.clone());
+                for effect in effects.iter() {
+                    effect.apply(self);
+                }
+                self.move_to(to, printer);
+            }
+            None => printer.print(&format!("You can't go {} from here.", direction.name()), PrintStyle::Normal),
+        }
+    }
+
+    fn handle_open(&mut self, command: &Command, printer: &mut Printer) {
+        match command.object {
+            Some(Noun::Exit(direction)) if self.is_locked(self.location, direction) => {
+                printer.print("It's locked, and you have no way to open it.", PrintStyle::Normal)
+            }
+            Some(Noun::Exit(_)) => printer.print("It's already open.", PrintStyle::Normal),
+            Some(noun) => printer.print(&format!("You can't open {}.", self.noun_name(noun)), PrintStyle::Normal),
+            None => printer.print("What do you want to open?", PrintStyle::Normal),
+        }
+    }
+
+    fn handle_run(&mut self, command: &Command, printer: &mut Printer) {
+        match command.object {
+            Some(Noun::Exit(direction)) => self.go(direction, printer),
+            _ => printer
+                .print("You stumble a few steps in the low gravity. Running nowhere helps no one.", PrintStyle::Normal),
+        }
+    }
+
+    fn handle_save(&mut self, printer: &mut Printer) {
+        match self.save(&self.save_path, printer) {
+            Ok(()) => printer.print("Game saved.", PrintStyle::Normal),
+            Err(e) => printer.print(&format!("The game could not be saved: {}.", escape(&e)), PrintStyle::Normal),
+        }
+    }
+
+    fn handle_restore(&mut self, printer: &mut Printer) {
+        match self.restore(&self.save_path.clone(), printer) {
+            Ok(()) => printer.print("Game restored.", PrintStyle::Normal),
+            Err(e) => printer.print(&format!("The game could not be restored: {}.", escape(&e)), PrintStyle::Normal),
+        }
+    }
+
+    fn handle_take(&mut self, command: &Command, printer: &mut Printer) {
+        match command.object {
+            Some(Noun::Prop(id)) if self.items.get(id).is(PropTypes::Fixed) => {
+                printer.print(&format!("The {} won't budge.", self.items.get(id).name), PrintStyle::Normal)
+            }
+            Some(Noun::Prop(id)) if self.items.is_carried(id) => {
+                printer.print(&format!("You already have the {}.", self.items.get(id).name), PrintStyle::Normal)
+            }
+            Some(Noun::Prop(id)) => {
+                self.items.place(id, Placement::Player);
+                printer.print(&format!("You take the {}.", self.items.get(id).name), PrintStyle::Normal);
+            }
+            Some(noun) => printer.print(&format!("You can't take {}.", self.noun_name(noun)), PrintStyle::Normal),
+            None => printer.print("What do you want to take?", PrintStyle::Normal),
+        }
+    }
+
+    fn handle_talk(&mut self, command: &Command, printer: &mut Printer) {
+        match command.object {
+            Some(Noun::Person(person)) if self.can_reach(person) => self.start_conversation(person, printer),
+            Some(Noun::Person(person)) => {
+                printer.print(&format!("{} isn't here to talk to.", person.name()), PrintStyle::Normal)
+            }
+            Some(noun) => printer.print(&format!("You can't talk to {}.", self.noun_name(noun)), PrintStyle::Normal),
+            None => printer.print("Who do you want to talk to?", PrintStyle::Normal),
+        }
+    }
+
+    fn handle_use(&mut self, command: &Command, printer: &mut Printer) {
+        match (command.object, command.indirect) {
+            (Some(Noun::Prop(id)), None) => match self.items.get(id).usage.clone() {
+                Some(usage) => {
+                    for effect in usage.effects.iter() {
+                        effect.apply(self);
+                    }
+                    printer.print(&self.variables.interpolate(&usage.text), PrintStyle::Normal);
+                }
+                None => printer.print(&self.variables.interpolate(&self.items.get(id).description), PrintStyle::Normal),
+            },
+            (Some(noun), Some(target)) => printer.print(
+                &format!("Using {} on {} does nothing.", self.noun_name(noun), self.noun_name(target)),
+                PrintStyle::Normal,
+            ),
+            (Some(noun), None) => {
+                printer.print(&format!("You can't use {}.", self.noun_name(noun)), PrintStyle::Normal)
+            }
+            (None, _) => printer.print("What do you want to use?", PrintStyle::Normal),
+        }
+    }
+}
@@ -1 +1,9 @@
+pub mod conditions;
+pub mod effects;
+pub mod expression;
 pub mod game_state;
+mod handlers;
+pub mod oxygen;
+pub mod save;
+pub mod triggers;
+pub mod variables;
@@ -0,0 +1,108 @@
+use crate::game::game_state::GameState;
+use crate::game::variables::Value;
+use crate::narration::narrations::NarrationID;
+use crate::printer::{PrintStyle, Printer};
+use serde::{Deserialize, Serialize};
+
+/// How the suit's oxygen runs down: not at all, a percent every turn, or in real time.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Difficulty {
+    Story,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn find(word: &str) -> Option<Difficulty> {
+        match word.to_uppercase().as_str() {
+            "STORY" => Some(Difficulty::Story),
+            "NORMAL" => Some(Difficulty::Normal),
+            "HARD" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Story => "story",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Difficulty::Story => "your oxygen never runs out",
+            Difficulty::Normal => "your oxygen runs down with every move",
+            Difficulty::Hard => "your oxygen runs down in real time",
+        }
+    }
+}
+
+/// Central's voice once the oxygen drops below a level.
+#[derive(Debug, Clone)]
+pub struct OxygenWarning {
+    pub below: i64,
+    pub text: String,
+}
+
+/// The suit's supply, kept in an int variable so the content can show it and refill it.
+#[derive(Debug, Clone)]
+pub struct Oxygen {
+    pub variable: String,
+    pub seconds_per_percent: f32,
+    pub warnings: Vec<OxygenWarning>,
+    pub out: NarrationID,
+}
+
+impl GameState {
+    /// A command has been carried out. On normal difficulty that costs a percent of oxygen.
+    pub fn end_turn(&mut self, printer: &mut Printer) {
+        self.turns += 1;
+        if self.difficulty == Difficulty::Normal {
+            self.breathe(1, printer);
+        }
+        self.move_people(printer);
+        self.check_triggers(printer);
+    }
+
+    /// Time going by in the main loop, for timed triggers. Only hard difficulty uses oxygen for it.
+    pub fn pass_time(&mut self, seconds: f32, printer: &mut Printer) {
+        if self.game_over {
+            return;
+        }
+        self.playing += seconds;
+        self.check_triggers(printer);
+        let Some(oxygen) = self.oxygen.as_ref().filter(|_| self.difficulty == Difficulty::Hard) else {
+            return;
+        };
+        self.breathing += seconds;
+        let used = (self.breathing / oxygen.seconds_per_percent).floor();
+        if used >= 1.0 {
+            self.breathing -= used * oxygen.seconds_per_percent;
+            self.breathe(used as i64, printer);
+        }
+    }
+
+    fn breathe(&mut self, used: i64, printer: &mut Printer) {
+        let Some(oxygen) = self.oxygen.clone() else {
+            return;
+        };
+        let before = self.variables.int(&oxygen.variable);
+        let after = (before - used).max(0);
+        self.set_variable(&oxygen.variable, Value::Int(after));
+
+        // Only the most urgent warning crossed, if a lot went at once
+        let crossed = oxygen.warnings.iter().filter(|warning| before >= warning.below && after < warning.below);
+        if let Some(warning) = crossed.min_by_key(|warning| warning.below) {
+            printer.print(&format!("\n{}", self.variables.interpolate(&warning.text)), PrintStyle::AI);
+        }
+
+        if after == 0 && before > 0 {
+            self.conversation = None;
+            self.game_over = true;
+            let narration = self.narrations.get(&oxygen.out).unwrap().text(self);
+            printer.print(&format!("\n{}", self.variables.interpolate(&narration)), PrintStyle::Normal);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::dialogue::logic::{DialogueNodeID, DialoguePersonID};
+use crate::dialogue::people::{PersonState, Whereabouts};
+use crate::game::game_state::GameState;
+use crate::game::oxygen::Difficulty;
+use crate::game::variables::Value;
+use crate::location::direction::Direction;
+use crate::location::locations::LocationID;
+use crate::printer::{Printer, TranscriptEntry};
+use crate::props::enums::PropsID;
+use crate::props::placement::Placement;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const SAVE_FILE: &str = "selenic-echo.save";
+
+/// Bump whenever the layout below changes, older saves are then refused rather than misread.
+const SAVE_VERSION: u32 = 6;
+
+/// Just the version, read first so an old save is refused with a clear message instead of a missing field.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+/// The dynamic part of the game: where the player, items and people are, the story state and what's been printed.
+/// The content files aren't saved, they're read again at startup and the save is checked against them.
+#[derive(Serialize, Deserialize)]
+struct SaveGame {
+    version: u32,
+    location: LocationID,
+    conversation: Option<DialogueNodeID>,
+    variables: Vec<(String, Value)>,
+    visits: Vec<(LocationID, u32)>,
+    locked_exits: Vec<(LocationID, Direction)>,
+    dialogue_visits: Vec<(DialogueNodeID, u32)>,
+    placements: Vec<(PropsID, Placement)>,
+    difficulty: Difficulty,
+    turns: u32,
+    game_over: bool,
+    fired: Vec<String>,
+    playing: f32,
+    people: Vec<(DialoguePersonID, PersonState)>,
+    transcript: Vec<TranscriptEntry>,
+}
+
+impl GameState {
+    pub fn save(&self, path: &Path, printer: &Printer) -> Result<(), String> {
+        let save = SaveGame {
+            version: SAVE_VERSION,
+            location: self.location,
+            conversation: self.conversation,
+            variables: self.variables.values(),
+            visits: self.visits.iter().map(|(id, count)| (*id, *count)).collect(),
+            locked_exits: self.locked_exits.iter().copied().collect(),
+            dialogue_visits: self.dialogue_visits.iter().map(|(id, count)| (*id, *count)).collect(),
+            placements: self.items.placements(),
+            difficulty: self.difficulty,
+            turns: self.turns,
+            game_over: self.game_over,
+            fired: self.fired.iter().cloned().collect(),
+            playing: self.playing,
+            people: self.person_states.iter().map(|(id, person)| (*id, person.clone())).collect(),
+            transcript: printer.transcript().to_vec(),
+        };
+        let text = toml::to_string(&save).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn restore(&mut self, path: &Path, printer: &mut Printer) -> Result<(), String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let header = toml::from_str::<SaveHeader>(&text).map_err(|e| e.to_string())?;
+        if header.version != SAVE_VERSION {
+            return Err(format!("save file is version {}, this game reads version {}", header.version, SAVE_VERSION));
+        }
+        let save = toml::from_str::<SaveGame>(&text).map_err(|e| e.to_string())?;
+
+        // The content files may have changed since the game was saved
+        if !self.locations.contains_key(&save.location) {
+            return Err(format!("the saved location \"{:?}\" no longer exists", save.location));
+        }
+        if let Some(id) = save.conversation.filter(|id| !self.dialogues.contains_key(id)) {
+            return Err(format!("the saved conversation \"{:?}\" no longer exists", id));
+        }
+        for (id, person) in save.people.iter() {
+            if !self.people.contains_key(id) {
+                return Err(format!("{} is no longer in the game", id.name()));
+            }
+            if let Whereabouts::At(location) = person.whereabouts {
+                if !self.locations.contains_key(&location) {
+                    return Err(format!("{}'s saved location \"{:?}\" no longer exists", id.name(), location));
+                }
+            }
+        }
+        let mut variables = self.variables.clone();
+        for (name, value) in save.variables {
+            variables.set(&name, value).map_err(|e| format!("the saved game doesn't fit the content, {}", e))?;
+        }
+
+        self.location = save.location;
+        self.variables = variables;
+        self.conversation = save.conversation;
+        self.visits = save.visits.into_iter().collect();
+        self.locked_exits = save.locked_exits.into_iter().collect();
+        self.dialogue_visits = save.dialogue_visits.into_iter().collect();
+        for (id, placement) in save.placements {
+            self.items.place(id, placement);
+        }
+        self.difficulty = save.difficulty;
+        self.turns = save.turns;
+        self.breathing = 0.0;
+        self.game_over = save.game_over;
+        self.fired = save.fired.into_iter().collect();
+        self.playing = save.playing;
+        self.person_states.extend(save.people);
+        printer.restore(save.transcript);
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::dialogue::logic::DialoguePersonID;
+use crate::game::conditions::Condition;
+use crate::game::effects::Effect;
+use crate::game::game_state::GameState;
+use crate::location::locations::LocationID;
+use crate::narration::narrations::NarrationID;
+use crate::printer::{PrintStyle, Printer};
+use crate::props::enums::PropsID;
+
+/// What sets a trigger off, besides its condition.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Enter(LocationID),
+    Turns(u32),
+    Seconds(f32),
+    Taken(PropsID),
+}
+
+/// A story beat that happens once, the first time its event has happened and its condition holds.
+#[derive(Debug, Clone)]
+pub struct Trigger {
+    pub id: String,
+    pub event: Option<Event>,
+    pub condition: Option<Condition>,
+    pub narration: Option<NarrationID>,
+    pub text: Option<String>,
+    pub talk: Option<DialoguePersonID>,
+    pub effects: Vec<Effect>,
+}
+
+impl Event {
+    fn happened(&self, game_state: &GameState) -> bool {
+        match self {
+            Event::Enter(id) => game_state.location == *id,
+            Event::Turns(turns) => game_state.turns >= *turns,
+            Event::Seconds(seconds) => game_state.playing >= *seconds,
+            Event::Taken(id) => game_state.items.is_carried(*id),
+        }
+    }
+}
+
+impl GameState {
+    /// Fire any triggers that are due, in the order they're written. Story beats wait for a conversation
+    /// to finish, and stop once the story has ended.
+    pub fn check_triggers(&mut self, printer: &mut Printer) {
+        if self.in_conversation() || self.game_over {
+            return;
+        }
+        let due = self
+            .triggers
+            .iter()
+            .filter(|trigger| !self.fired.contains(&trigger.id))
+            .filter(|trigger| trigger.event.as_ref().is_none_or(|event| event.happened(self)))
+            .filter(|trigger| trigger.condition.as_ref().is_none_or(|condition| condition.holds(self)))
+            .cloned()
+            .collect::<Vec<Trigger>>();
+        for trigger in due {
+            // A conversation one of them started holds the rest back until it's over
+            if self.in_conversation() || self.game_over {
+                break;
+            }
+            self.fired.insert(trigger.id.clone());
+            for effect in trigger.effects.iter() {
+                effect.apply(self);
+            }
+            if let Some(id) = trigger.narration {
+                let narration = self.narrations.get(&id).unwrap().text(self);
+                printer.print(&format!("\n{}", self.variables.interpolate(&narration)), PrintStyle::Normal);
+            }
+            if let Some(text) = trigger.text.as_ref() {
+                printer.print(&format!("\n{}", self.variables.interpolate(text)), PrintStyle::Normal);
+            }
+            if let Some(person) = trigger.talk {
+                printer.print("\n", PrintStyle::Normal);
+                self.start_conversation(person, printer);
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::markup::escape;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// What a story variable holds. Enum values are text, checked against the declared list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariableType {
+    Bool,
+    Int,
+    String,
+    Enum(Vec<String>),
+}
+
+impl VariableType {
+    pub fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (VariableType::Bool, Value::Bool(_)) | (VariableType::Int, Value::Int(_)) => true,
+            (VariableType::String, Value::Text(_)) => true,
+            (VariableType::Enum(values), Value::Text(value)) => values.contains(value),
+            _ => false,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VariableType::Bool => "bool",
+            VariableType::Int => "int",
+            VariableType::String => "string",
+            VariableType::Enum(_) => "enum",
+        }
+    }
+}
+
+/// A variable as declared in the content, with the value a new game starts with.
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub name: String,
+    pub kind: VariableType,
+    pub start: Value,
+}
+
+/// The story's state beyond where things are: flags, counters and the like.
+#[derive(Clone)]
+pub struct Variables {
+    types: HashMap<String, VariableType>,
+    values: HashMap<String, Value>,
+}
+
+impl Variables {
+    pub fn new(declared: Vec<Variable>) -> Variables {
+        let mut types = HashMap::new();
+        let mut values = HashMap::new();
+        for variable in declared.into_iter() {
+            types.insert(variable.name.clone(), variable.kind);
+            values.insert(variable.name, variable.start);
+        }
+
+        Variables {
+            types,
+            values,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    pub fn is_true(&self, name: &str) -> bool {
+        self.get(name) == Some(&Value::Bool(true))
+    }
+
+    pub fn int(&self, name: &str) -> i64 {
+        match self.get(name) {
+            Some(Value::Int(value)) => *value,
+            _ => 0,
+        }
+    }
+
+    /// Only declared variables can be set, and only to a value of their type.
+    pub fn set(&mut self, name: &str, value: Value) -> Result<(), String> {
+        match self.types.get(name) {
+            Some(kind) if kind.accepts(&value) => {
+                self.values.insert(name.to_string(), value);
+                Ok(())
+            }
+            Some(kind) => Err(format!("{} is {}, it can't be set to {}", name, kind.name(), value)),
+            None => Err(format!("there is no variable {}", name)),
+        }
+    }
+
+    /// Every value, sorted by name, for saving.
+    pub fn values(&self) -> Vec<(String, Value)> {
+        let mut values = self.values.iter().map(|(name, value)| (name.clone(), value.clone())).collect::<Vec<_>>();
+        values.sort_by(|a, b| a.0.cmp(&b.0));
+        values
+    }
+
+    /// Fill in {name} with the variable's value, escaped so it prints as it is. Write {{ for a literal brace.
+    pub fn interpolate(&self, text: &str) -> String {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start + 1..];
+            if let Some(after) = rest.strip_prefix('{') {
+                result.push('{');
+                rest = after;
+                continue;
+            }
+            match rest.find('}') {
+                Some(end) => {
+                    match self.get(&rest[..end]) {
+                        Some(value) => result.push_str(&escape(&value.to_string())),
+                        None => result.push_str(&format!("{{{}}}", &rest[..end])),
+                    }
+                    rest = &rest[end + 1..];
+                }
+                None => result.push('{'),
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+/// The names inside {braces} in some text, for checking they're all declared.
+pub fn references(text: &str) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find('{').map(|start| start + offset) {
+        if text[start + 1..].starts_with('{') {
+            offset = start + 2;
+            continue;
+        }
+        match text[start..].find('}') {
+            Some(end) => {
+                found.push((start, &text[start + 1..start + end]));
+                offset = start + end + 1;
+            }
+            None => break,
+        }
+    }
+    found
+}
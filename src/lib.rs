@@ -0,0 +1,16 @@
+#[cfg(feature = "gui")]
+pub mod app_state;
+pub mod content;
+pub mod dialogue;
+pub mod game;
+pub mod location;
+pub mod markup;
+pub mod narration;
+pub mod parser;
+pub mod printer;
+pub mod props;
+#[cfg(feature = "gui")]
+pub mod screen;
+#[cfg(feature = "gui")]
+pub mod skia;
+pub mod tty;
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+    Up,
+    Down,
+    In,
+    Out,
+}
+
+impl Direction {
+    pub fn name(&self) -> &str {
+        match self {
+            Direction::North => "north",
+            Direction::NorthEast => "northeast",
+            Direction::East => "east",
+            Direction::SouthEast => "southeast",
+            Direction::South => "south",
+            Direction::SouthWest => "southwest",
+            Direction::West => "west",
+            Direction::NorthWest => "northwest",
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::In => "in",
+            Direction::Out => "out",
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::location::direction::Direction;
+use std::collections::HashMap;
+
+pub struct DirectionLookup {
+    lookups: HashMap<String, Direction>,
+}
+
+impl Default for DirectionLookup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DirectionLookup {
+    pub fn new() -> DirectionLookup {
+        let mut lookups = HashMap::new();
+        lookups.insert("N".to_string(), Direction::North);
+        lookups.insert("NORTH".to_string(), Direction::North);
+        lookups.insert("NE".to_string(), Direction::NorthEast);
+        lookups.insert("NORTHEAST".to_string(), Direction::NorthEast);
+        lookups.insert("E".to_string(), Direction::East);
+        lookups.insert("EAST".to_string(), Direction::East);
+        lookups.insert("SE".to_string(), Direction::SouthEast);
+        lookups.insert("SOUTHEAST".to_string(), Direction::SouthEast);
+        lookups.insert("S".to_string(), Direction::South);
+        lookups.insert("SOUTH".to_string(), Direction::South);
+        lookups.insert("SW".to_string(), Direction::SouthWest);
+        lookups.insert("SOUTHWEST".to_string(), Direction::SouthWest);
+        lookups.insert("W".to_string(), Direction::West);
+        lookups.insert("WEST".to_string(), Direction::West);
+        lookups.insert("NW".to_string(), Direction::NorthWest);
+        lookups.insert("NORTHWEST".to_string(), Direction::NorthWest);
+
+        lookups.insert("U".to_string(), Direction::Up);
+        lookups.insert("UP".to_string(), Direction::Up);
+        lookups.insert("D".to_string(), Direction::Down);
+        lookups.insert("DOWN".to_string(), Direction::Down);
+
+        lookups.insert("IN".to_string(), Direction::In);
+        lookups.insert("INSIDE".to_string(), Direction::In);
+        lookups.insert("OUT".to_string(), Direction::Out);
+        lookups.insert("OUTSIDE".to_string(), Direction::Out);
+        lookups.insert("EXIT".to_string(), Direction::Out);
+
+        DirectionLookup {
+            lookups,
+        }
+    }
+
+    pub fn find_direction(&self, key: &str) -> Option<&Direction> {
+        self.lookups.get(&key.to_uppercase())
+    }
+
+    /// Every direction with its short forms, offered anywhere in a command since GO is optional.
+    pub fn words(&self) -> Vec<&str> {
+        self.lookups.keys().map(String::as_str).collect()
+    }
+}
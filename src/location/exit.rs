@@ -0,0 +1,27 @@
+use crate::game::conditions::Condition;
+use crate::game::effects::Effect;
+use crate::location::direction::Direction;
+use crate::location::locations::LocationID;
+
+#[derive(Debug)]
+pub struct Exit {
+    pub direction: Direction,
+    pub names: Vec<String>,
+    pub to: LocationID,
+    pub locked: Option<String>,
+    pub visible_when: Option<Condition>,
+    pub effects: Vec<Effect>,
+}
+
+impl Exit {
+    pub fn matches(&self, key: &str) -> bool {
+        self.names.iter().any(|name| name.eq_ignore_ascii_case(key))
+    }
+
+    pub fn describe(&self) -> String {
+        match self.names.first() {
+            Some(name) => format!("{} ({})", self.direction.name(), name.to_lowercase()),
+            None => self.direction.name().to_string(),
+        }
+    }
+}
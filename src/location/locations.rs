@@ -1,23 +1,13 @@
-use crate::location;
+use crate::content_id;
+use crate::location::exit::Exit;
 use crate::narration::narrations::NarrationID;
-use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct Location {
     pub id: LocationID,
+    pub name: String,
     pub narration_id: NarrationID,
+    pub exits: Vec<Exit>,
 }
 
-#[derive(Hash, Debug, Eq, PartialEq, Clone, Copy)]
-pub enum LocationID {
-    InitialLocation,
-}
-
-pub fn get_locations() -> HashMap<LocationID, Location> {
-    let mut map = HashMap::new();
-    {
-        let l = location!(LocationID::InitialLocation, NarrationID::Awake);
-        map.insert(l.id, l);
-    }
-    map
-}
+content_id!(LocationID);
@@ -1,2 +1,4 @@
+pub mod direction;
+pub mod direction_lookup;
+pub mod exit;
 pub mod locations;
-mod macros;
@@ -1,24 +1,29 @@
-use crate::app_state::AppState;
-use crate::game::game_state::GameState;
-use crate::parser::process::Parser;
-use crate::printer::Printer;
-use crate::skia::Skia;
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::Mod;
 use sdl2::video::GLProfile;
+use sdl2::VideoSubsystem;
+use selenic_echo::app_state::AppState;
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use selenic_echo::game::game_state::GameState;
+use selenic_echo::parser::process::Parser;
+use selenic_echo::printer::Printer;
+use selenic_echo::screen::Screen;
+use selenic_echo::skia::Skia;
+use skia_safe::Point;
+use std::path::Path;
 use std::process::exit;
 use std::time::{Duration, Instant};
 
-mod app_state;
-mod dialogue;
-mod game;
-mod location;
-mod narration;
-mod parser;
-mod printer;
-mod props;
-mod skia;
-
 fn main() {
+    // Content first, so broken files are reported before a window opens
+    let content = match load(Path::new(CONTENT_DIR)) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+
     // Initialize SDL2
     let sdl = sdl2::init().unwrap();
     let video_subsystem = sdl.video().unwrap();
@@ -29,7 +34,8 @@ fn main() {
     gl_attr.set_context_version(3, 3); // OpenGL 3.3
 
     // Create an SDL2 window
-    let window = video_subsystem.window("Selenic Echo", 1400, 900).opengl().allow_highdpi().build().unwrap();
+    let window =
+        video_subsystem.window("Selenic Echo", 1400, 900).opengl().allow_highdpi().resizable().build().unwrap();
 
     // Create an OpenGL context
     let _gl_context = window.gl_create_context().unwrap();
@@ -40,24 +46,7 @@ fn main() {
 
     // Get display index (typically 0 is the default display)
     let display_index = 0;
-
-    // Get DPI information
-    let mut dpi = 0.0;
-    match video_subsystem.display_dpi(display_index) {
-        Ok((d_dpi, hdpi, v_dpi)) => {
-            println!("Diagonal DPI: {}", d_dpi);
-            println!("Horizontal DPI: {}", hdpi);
-            println!("Vertical DPI: {}", v_dpi);
-
-            // Calculate scaling factor
-            dpi = hdpi / 96.0; // 96 DPI is considered the default "normal" DPI
-            println!("Scaling factor: {}", dpi);
-        }
-        Err(e) => {
-            eprintln!("Could not get DPI information: {}", e);
-        }
-    }
-    let dpi = dpi.floor();
+    let dpi = display_scale(&video_subsystem, display_index, 1.0);
 
     // Event pump for SDL2 events
     let mut event_pump = sdl.event_pump().unwrap();
@@ -69,25 +58,33 @@ fn main() {
 
     // App state, skia etc.
     let mut app_state = AppState::new(window, dpi);
-    let mut game_state = GameState::new();
+    let mut game_state = GameState::new(content);
     let mut parser = Parser::new();
     let mut skia = Skia::new(&app_state);
     unsafe {
         skia.flush(app_state.gfx.dpi, 0.0);
     }
     let start = Instant::now();
-    let mut printer = Printer::new(&skia);
+    let mut printer = Printer::new();
+    let mut screen = Screen::new(&skia);
     game_state.start(&mut printer);
+    let mut previous_frame = Instant::now();
     loop {
         // Measure the time it took to render the previous frame
         let current_time = Instant::now();
         app_state.phase = (current_time.duration_since(start).as_millis() as f32 / 128.0) % 8.0;
 
+        // The clock only runs while the player can act, not while text is still appearing
+        if !printer.is_writing() {
+            game_state.pass_time(current_time.duration_since(previous_frame).as_secs_f32(), &mut printer);
+        }
+        previous_frame = current_time;
+
         // Render!
         skia.set_matrix(&app_state.gfx);
-        printer.print_render(&mut skia, &app_state.gfx, app_state.phase);
+        screen.print_render(&mut printer, &mut skia, &app_state.gfx, app_state.phase);
         if !printer.is_writing() {
-            parser.print(&mut skia, &mut printer);
+            parser.print(&mut skia, &mut screen, &game_state);
         }
         unsafe {
             skia.flush(app_state.gfx.dpi, start.elapsed().as_secs_f32());
@@ -114,13 +111,46 @@ fn main() {
                 // Keyboard
                 Event::KeyDown {
                     keycode: Some(key),
+                    keymod,
                     ..
                 } => {
+                    let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD | Mod::LGUIMOD | Mod::RGUIMOD);
                     match key {
                         sdl2::keyboard::Keycode::Backspace => {
                             parser.process_backspace();
                         }
 
+                        // Line editing
+                        sdl2::keyboard::Keycode::Tab => parser.process_tab(&game_state),
+                        sdl2::keyboard::Keycode::Delete => parser.edit_line().delete(),
+                        sdl2::keyboard::Keycode::Left => parser.edit_line().left(),
+                        sdl2::keyboard::Keycode::Right => parser.edit_line().right(),
+                        sdl2::keyboard::Keycode::Home => parser.edit_line().home(),
+                        sdl2::keyboard::Keycode::End => parser.edit_line().end(),
+                        sdl2::keyboard::Keycode::Up => parser.edit_line().recall_previous(),
+                        sdl2::keyboard::Keycode::Down => parser.edit_line().recall_next(),
+                        sdl2::keyboard::Keycode::W if ctrl => parser.edit_line().delete_word(),
+                        sdl2::keyboard::Keycode::V if ctrl => match video_subsystem.clipboard().clipboard_text() {
+                            Ok(text) => parser.edit_line().insert(&text),
+                            Err(e) => eprintln!("Could not paste: {}", e),
+                        },
+
+                        sdl2::keyboard::Keycode::F11 => {
+                            app_state.toggle_fullscreen();
+                        }
+
+                        sdl2::keyboard::Keycode::PageUp => {
+                            screen.scroll_pages(1, &app_state.gfx);
+                        }
+
+                        sdl2::keyboard::Keycode::PageDown => {
+                            screen.scroll_pages(-1, &app_state.gfx);
+                        }
+
+                        sdl2::keyboard::Keycode::Return if printer.is_writing() => {
+                            screen.skip(&printer);
+                        }
+
                         sdl2::keyboard::Keycode::Return => {
                             parser.process_enter(&mut game_state, &mut printer);
                         }
@@ -134,11 +164,54 @@ fn main() {
                     text,
                     ..
                 } => {
-                    if !printer.is_writing() {
+                    // Typing while text is still appearing skips ahead instead
+                    if printer.is_writing() {
+                        screen.skip(&printer);
+                    } else {
+                        screen.scroll_to_bottom();
                         parser.process_key(text);
                     }
                 }
 
+                // Clicking a link enters its command, clicking anywhere skips ahead
+                Event::MouseButtonDown {
+                    x,
+                    y,
+                    ..
+                } => {
+                    if printer.is_writing() {
+                        screen.skip(&printer);
+                    } else if let Some(link) = screen.link_at(Point::new(x as f32, y as f32)) {
+                        parser.submit(&link, &mut game_state, &mut printer);
+                    }
+                }
+
+                // Scroll back through earlier text, three lines a notch
+                Event::MouseWheel {
+                    y,
+                    ..
+                } => {
+                    screen.scroll_lines(y * 3);
+                }
+
+                // Window resized, maybe by going fullscreen, or moved to another display
+                Event::Window {
+                    win_event: WindowEvent::Resized(width, height) | WindowEvent::SizeChanged(width, height),
+                    ..
+                } if app_state.resize(width, height) => {
+                    skia.resize(&app_state.gfx);
+                    screen.reflow(&app_state.gfx);
+                }
+
+                Event::Window {
+                    win_event: WindowEvent::DisplayChanged(display_index),
+                    ..
+                } => {
+                    app_state.gfx.dpi = display_scale(&video_subsystem, display_index, app_state.gfx.dpi);
+                    skia.resize(&app_state.gfx);
+                    screen.reflow(&app_state.gfx);
+                }
+
                 _ => {}
             }
         }
@@ -146,3 +219,16 @@ fn main() {
         app_state.gfx.window.gl_swap_window();
     }
 }
+
+/// How much to scale everything up by for the display, 1 for a normal 96 DPI screen. Keeps the previous scale
+/// if the display can't say.
+fn display_scale(video_subsystem: &VideoSubsystem, display_index: i32, previous: f32) -> f32 {
+    match video_subsystem.display_dpi(display_index) {
+        // 96 DPI is considered the default "normal" DPI
+        Ok((_, hdpi, _)) => (hdpi / 96.0).floor().max(1.0),
+        Err(e) => {
+            eprintln!("Could not get DPI information: {}", e);
+            previous
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use crate::printer::PrintStyle;
+use std::mem;
+
+// Text is words separated by spaces. A line break in the text starts a new paragraph. Tags:
+//   <b>bold</b>  <i>italics</i>  <color=red>colour</color>, named or #rrggbb
+//   <ai>Central's voice</ai>  <echo>Echo's voice</echo>
+//   <speed=0.5>slower</speed>  <pause=800>  <br> for a line break
+//   <link=LOOK AT HELMET>helmet</link>, clicking enters the command
+// Tags nest but must close in order. Write << for a literal <.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Format {
+    pub style: PrintStyle,
+    pub bold: bool,
+    pub italic: bool,
+    pub color: Option<(u8, u8, u8)>,
+    pub speed: f32,
+    pub link: Option<String>,
+}
+
+impl Format {
+    pub fn new(style: PrintStyle) -> Format {
+        Format {
+            style,
+            bold: false,
+            italic: false,
+            color: None,
+            speed: 1.0,
+            link: None,
+        }
+    }
+}
+
+/// Part of a word in one format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    pub text: String,
+    pub format: Format,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueueItem {
+    /// A word, in several runs when the format changes part way through, e.g. <b>LAB</b>.
+    Word(Vec<Run>),
+    LineBreak,
+    Paragraph,
+    Pause(u64),
+}
+
+/// What went wrong, and how many bytes into the text.
+#[derive(Debug)]
+pub struct MarkupError {
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Make any text safe to print as it is, e.g. what the player typed.
+pub fn escape(text: &str) -> String {
+    text.replace('<', "<<")
+}
+
+pub fn parse(text: &str, style: PrintStyle) -> Result<Vec<QueueItem>, MarkupError> {
+    let mut items = Vec::new();
+    let mut word = Vec::<Run>::new();
+    let mut format = Format::new(style);
+    let mut open = Vec::<(String, Format, usize)>::new();
+
+    let mut chars = text.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let error = |message: String| MarkupError {
+            offset,
+            message,
+        };
+        match c {
+            '<' if chars.peek().is_some_and(|(_, next)| *next == '<') => {
+                chars.next();
+                push_char(&mut word, '<', &format);
+            }
+            '<' => {
+                let mut tag = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '>')) => break,
+                        Some((_, '\n')) | None => return Err(error("a tag is missing its >".to_string())),
+                        Some((_, c)) => tag.push(c),
+                    }
+                }
+
+                // Closing tags put back the format from before the opening one
+                if let Some(name) = tag.strip_prefix('/') {
+                    match open.pop() {
+                        Some((opened, previous, _)) if opened == name => format = previous,
+                        Some((opened, _, _)) => {
+                            return Err(error(format!("</{}> found where </{}> was expected", name, opened)))
+                        }
+                        None => return Err(error(format!("</{}> closes a tag that was never opened", name))),
+                    }
+                    continue;
+                }
+
+                let (name, value) = match tag.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (tag.as_str(), None),
+                };
+                let previous = format.clone();
+                match (name, value) {
+                    ("br", None) => {
+                        flush(&mut word, &mut items);
+                        items.push(QueueItem::LineBreak);
+                        continue;
+                    }
+                    ("pause", Some(value)) => {
+                        let millis = value.parse::<u64>().map_err(|_| error(format!("bad pause \"{}\"", value)))?;
+                        flush(&mut word, &mut items);
+                        items.push(QueueItem::Pause(millis));
+                        continue;
+                    }
+                    ("b", None) => format.bold = true,
+                    ("i", None) => format.italic = true,
+                    ("ai", None) => format.style = PrintStyle::AI,
+                    ("echo", None) => format.style = PrintStyle::Echo,
+                    ("color", Some(value)) => {
+                        format.color = Some(parse_color(value).ok_or(error(format!("unknown colour \"{}\"", value)))?)
+                    }
+                    ("speed", Some(value)) => {
+                        format.speed = value
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|speed| *speed > 0.0)
+                            .ok_or(error(format!("bad speed \"{}\"", value)))?
+                    }
+                    ("link", Some(value)) if !value.trim().is_empty() => format.link = Some(value.trim().to_string()),
+                    ("b" | "i" | "ai" | "echo" | "br", Some(_)) => {
+                        return Err(error(format!("<{}> takes no value", name)))
+                    }
+                    ("color" | "speed" | "link" | "pause", _) => {
+                        return Err(error(format!("<{}> needs a value", name)))
+                    }
+                    _ => return Err(error(format!("unknown tag <{}>", tag))),
+                }
+                open.push((name.to_string(), previous, offset));
+            }
+            '\n' => {
+                flush(&mut word, &mut items);
+                items.push(QueueItem::Paragraph);
+            }
+            c if c.is_whitespace() => flush(&mut word, &mut items),
+            c => push_char(&mut word, c, &format),
+        }
+    }
+    flush(&mut word, &mut items);
+
+    match open.pop() {
+        Some((name, _, offset)) => Err(MarkupError {
+            offset,
+            message: format!("<{}> is never closed", name),
+        }),
+        None => Ok(items),
+    }
+}
+
+fn push_char(word: &mut Vec<Run>, c: char, format: &Format) {
+    match word.last_mut() {
+        Some(run) if run.format == *format => run.text.push(c),
+        _ => word.push(Run {
+            text: c.to_string(),
+            format: format.clone(),
+        }),
+    }
+}
+
+fn flush(word: &mut Vec<Run>, items: &mut Vec<QueueItem>) {
+    if !word.is_empty() {
+        items.push(QueueItem::Word(mem::take(word)));
+    }
+}
+
+fn parse_color(value: &str) -> Option<(u8, u8, u8)> {
+    match value {
+        "red" => Some((230, 60, 60)),
+        "orange" => Some((255, 150, 40)),
+        "yellow" => Some((255, 220, 60)),
+        "green" => Some((80, 210, 90)),
+        "cyan" => Some((60, 210, 230)),
+        "blue" => Some((80, 130, 255)),
+        "magenta" => Some((255, 0, 255)),
+        "white" => Some((255, 255, 255)),
+        "grey" | "gray" => Some((150, 150, 150)),
+        _ => {
+            let hex = value.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+            let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+            Some((channel(0)?, channel(2)?, channel(4)?))
+        }
+    }
+}
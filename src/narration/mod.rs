@@ -1,2 +1 @@
 pub mod narrations;
-mod macros;
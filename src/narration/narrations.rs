@@ -1,23 +1,137 @@
-use crate::narration;
-use std::collections::HashMap;
+use crate::content_id;
+use crate::game::conditions::Condition;
+use crate::game::expression::{parse_condition, ExpressionError, Names};
+use crate::game::game_state::GameState;
+use crate::game::variables::references;
 
-const AWAKE: &str = include_str!("awake.txt");
-
-#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
-pub enum NarrationID {
-    Awake,
-}
+content_id!(NarrationID);
 
 pub struct Narration {
     pub id: NarrationID,
-    pub text: String,
+    pub parts: Vec<NarrationPart>,
+}
+
+/// Narration text, with {if condition} ... {else} ... {end} blocks that are only told when it holds.
+pub enum NarrationPart {
+    Text(String),
+    If(Condition, Vec<NarrationPart>, Vec<NarrationPart>),
+}
+
+impl Narration {
+    /// The text as it reads right now, with the blocks that don't apply left out.
+    pub fn text(&self, game_state: &GameState) -> String {
+        let mut text = String::new();
+        resolve(&self.parts, game_state, &mut text);
+        text
+    }
+}
+
+fn resolve(parts: &[NarrationPart], game_state: &GameState, text: &mut String) {
+    for part in parts.iter() {
+        match part {
+            NarrationPart::Text(part) => text.push_str(part),
+            NarrationPart::If(condition, then, _) if condition.holds(game_state) => resolve(then, game_state, text),
+            NarrationPart::If(_, _, otherwise) => resolve(otherwise, game_state, text),
+        }
+    }
+}
+
+/// Where the {if}, {else} and {end} directives are: start, end and what's inside the braces.
+/// A directive on a line of its own takes the line break with it, so it leaves no blank line behind.
+pub fn directives(text: &str) -> Vec<(usize, usize, &str)> {
+    references(text)
+        .into_iter()
+        .filter(|(_, inside)| inside.starts_with("if ") || *inside == "else" || *inside == "end")
+        .map(|(start, inside)| {
+            let end = start + inside.len() + 2;
+            let alone = (start == 0 || text[..start].ends_with('\n')) && text[end..].starts_with('\n');
+            (
+                start,
+                if alone {
+                    end + 1
+                } else {
+                    end
+                },
+                inside,
+            )
+        })
+        .collect()
+}
+
+struct Block {
+    condition: Condition,
+    then: Vec<NarrationPart>,
+    otherwise: Option<Vec<NarrationPart>>,
+    offset: usize,
+}
+
+/// The parts that text goes in next: the innermost open block, or the top level.
+fn current<'a>(blocks: &'a mut [Block], parts: &'a mut Vec<NarrationPart>) -> &'a mut Vec<NarrationPart> {
+    match blocks.last_mut() {
+        Some(Block {
+            otherwise: Some(otherwise),
+            ..
+        }) => otherwise,
+        Some(block) => &mut block.then,
+        None => parts,
+    }
 }
 
-pub fn get_narrations() -> HashMap<NarrationID, Narration> {
-    let mut map = HashMap::<NarrationID, Narration>::new();
-    {
-        let n = narration!(NarrationID::Awake, AWAKE);
-        map.insert(n.id, n);
+fn push_text(parts: &mut Vec<NarrationPart>, text: &str) {
+    if !text.is_empty() {
+        parts.push(NarrationPart::Text(text.to_string()));
+    }
+}
+
+/// Split narration text into plain text and conditional blocks, which can be nested.
+pub fn parse_blocks(text: &str, names: &impl Names) -> Result<Vec<NarrationPart>, ExpressionError> {
+    let mut parts = Vec::new();
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut last = 0;
+    for (start, end, inside) in directives(text) {
+        push_text(current(&mut blocks, &mut parts), &text[last..start]);
+        last = end;
+        let unexpected = |message: &str| {
+            Err(ExpressionError {
+                offset: start,
+                message: message.to_string(),
+            })
+        };
+        match inside {
+            "else" => match blocks.last_mut() {
+                Some(block) if block.otherwise.is_none() => block.otherwise = Some(Vec::new()),
+                Some(_) => return unexpected("{else} appears twice in one {if}"),
+                None => return unexpected("{else} without an {if}"),
+            },
+            "end" => match blocks.pop() {
+                Some(block) => current(&mut blocks, &mut parts).push(NarrationPart::If(
+                    block.condition,
+                    block.then,
+                    block.otherwise.unwrap_or_default(),
+                )),
+                None => return unexpected("{end} without an {if}"),
+            },
+            _ => {
+                let condition = parse_condition(&inside[3..], names).map_err(|e| ExpressionError {
+                    offset: start + 4 + e.offset,
+                    message: e.message,
+                })?;
+                blocks.push(Block {
+                    condition,
+                    then: Vec::new(),
+                    otherwise: None,
+                    offset: start,
+                });
+            }
+        }
+    }
+    push_text(current(&mut blocks, &mut parts), &text[last..]);
+
+    match blocks.last() {
+        Some(block) => Err(ExpressionError {
+            offset: block.offset,
+            message: "{if} is never closed with {end}".to_string(),
+        }),
+        None => Ok(parts),
     }
-    map
 }
@@ -0,0 +1,44 @@
+use crate::dialogue::logic::DialoguePersonID;
+use crate::location::direction::Direction;
+use crate::parser::enums::Verbs;
+use crate::props::enums::PropsID;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Noun {
+    Prop(PropsID),
+    Person(DialoguePersonID),
+    Exit(Direction),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Preposition {
+    To,
+    On,
+    With,
+    In,
+    At,
+    From,
+}
+
+impl Preposition {
+    pub fn find(word: &str) -> Option<Preposition> {
+        match word.to_uppercase().as_str() {
+            "TO" => Some(Preposition::To),
+            "ON" | "ONTO" => Some(Preposition::On),
+            "WITH" | "USING" => Some(Preposition::With),
+            "IN" | "INTO" | "INSIDE" => Some(Preposition::In),
+            "AT" => Some(Preposition::At),
+            "FROM" => Some(Preposition::From),
+            _ => None,
+        }
+    }
+}
+
+/// A fully resolved player command, e.g. GIVE HELMET TO FIXER.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Command {
+    pub verb: Verbs,
+    pub object: Option<Noun>,
+    pub preposition: Option<Preposition>,
+    pub indirect: Option<Noun>,
+}
@@ -1,12 +1,16 @@
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
 pub enum Verbs {
     Close,
     Drop,
     Give,
+    Inventory,
     Look,
     Go,
     Open,
+    Restore,
     Run,
+    Save,
     Take,
     Talk,
-    Use
-}
\ No newline at end of file
+    Use,
+}
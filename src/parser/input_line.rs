@@ -0,0 +1,131 @@
+/// The line the player is typing, with a caret to edit at and earlier lines to recall.
+pub struct InputLine {
+    text: String,
+    caret: usize,
+    history: Vec<String>,
+    recalled: Option<usize>,
+    draft: String,
+}
+
+impl Default for InputLine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputLine {
+    pub fn new() -> InputLine {
+        InputLine {
+            text: String::new(),
+            caret: 0,
+            history: Vec::new(),
+            recalled: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Everything left of the caret, to work out where to draw it.
+    pub fn before_caret(&self) -> &str {
+        &self.text[..self.caret]
+    }
+
+    pub fn set(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.caret = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.set("");
+    }
+
+    /// Typed or pasted text. A paste can have anything in it, so line breaks and other control characters go.
+    pub fn insert(&mut self, text: &str) {
+        let text = text.chars().filter(|c| !c.is_control()).collect::<String>();
+        self.text.insert_str(self.caret, &text);
+        self.caret += text.len();
+    }
+
+    /// Swap what's between start and the caret for another word, when completing it.
+    pub fn replace_word(&mut self, start: usize, word: &str) {
+        self.text.replace_range(start..self.caret, word);
+        self.caret = start + word.len();
+    }
+
+    pub fn backspace(&mut self) {
+        if self.caret > 0 {
+            self.left();
+            self.text.remove(self.caret);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.caret < self.text.len() {
+            self.text.remove(self.caret);
+        }
+    }
+
+    /// Remove the word before the caret, and any spaces after it, like Ctrl+W in a terminal.
+    pub fn delete_word(&mut self) {
+        let before = self.before_caret().trim_end();
+        let start = before.rfind(char::is_whitespace).map(|index| index + 1).unwrap_or(0);
+        self.text.replace_range(start..self.caret, "");
+        self.caret = start;
+    }
+
+    pub fn left(&mut self) {
+        self.caret = self.before_caret().char_indices().last().map(|(index, _)| index).unwrap_or(0);
+    }
+
+    pub fn right(&mut self) {
+        self.caret += self.text[self.caret..].chars().next().map(|c| c.len_utf8()).unwrap_or(0);
+    }
+
+    pub fn home(&mut self) {
+        self.caret = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.caret = self.text.len();
+    }
+
+    /// Keep the line for recalling later, unless it's the same as the last one.
+    pub fn remember(&mut self) {
+        let line = self.text.trim();
+        if !line.is_empty() && self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_string());
+        }
+        self.recalled = None;
+    }
+
+    /// Step back through earlier lines. Whatever was being typed comes back after the most recent one.
+    pub fn recall_previous(&mut self) {
+        let index = match self.recalled {
+            Some(index) => index.saturating_sub(1),
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = self.text.clone();
+                self.history.len() - 1
+            }
+        };
+        self.recalled = Some(index);
+        self.set(&self.history[index].clone());
+    }
+
+    pub fn recall_next(&mut self) {
+        match self.recalled {
+            Some(index) if index + 1 < self.history.len() => {
+                self.recalled = Some(index + 1);
+                self.set(&self.history[index + 1].clone());
+            }
+            Some(_) => {
+                self.recalled = None;
+                self.set(&self.draft.clone());
+            }
+            None => {}
+        }
+    }
+}
@@ -1,3 +1,5 @@
-mod enums;
+pub mod command;
+pub mod enums;
+pub mod input_line;
 pub mod process;
-mod verb_lookup;
\ No newline at end of file
+mod verb_lookup;
@@ -1,88 +1,414 @@
+use crate::dialogue::person_lookup::PersonLookup;
 use crate::game::game_state::GameState;
+use crate::game::oxygen::Difficulty;
+use crate::location::direction_lookup::DirectionLookup;
+use crate::markup::escape;
+use crate::parser::command::{Command, Noun, Preposition};
+use crate::parser::enums::Verbs;
+use crate::parser::input_line::InputLine;
 use crate::parser::verb_lookup::VerbLookup;
-use crate::printer::{PrintStyle, Printer};
-use crate::skia::{Skia, FONT_SIZE};
+use crate::printer::{PrintStyle, Printer, TextSpeed};
+use crate::props::enums::PropsID;
+#[cfg(feature = "gui")]
+use crate::screen::Screen;
+#[cfg(feature = "gui")]
+use crate::skia::Skia;
+#[cfg(feature = "gui")]
 use skia_safe::utils::text_utils::Align;
-use skia_safe::{Color, Paint, PaintStyle, Point};
+#[cfg(feature = "gui")]
+use skia_safe::{Color, Point};
+use std::ops::Range;
+
+/// Shown before the line being typed, in the window and on a terminal.
+pub const PROMPT: &str = "# ";
+
+const FILLER_WORDS: [&str; 10] = ["THE", "A", "AN", "MY", "YOUR", "SOME", "THIS", "THAT", "THESE", "THOSE"];
+
+enum ParseError {
+    UnknownVerb(String),
+    UnknownNoun(String),
+    NotHere(String),
+    Unfinished,
+    Ambiguous {
+        candidates: Vec<PropsID>,
+        before: String,
+        after: String,
+    },
+}
+
+/// Tab pressed on a partly typed word. Pressing it again moves on to the next candidate.
+struct Completion {
+    start: usize,
+    candidates: Vec<String>,
+    index: usize,
+}
 
 pub struct Parser {
-    current_line: String,
-    paint: Paint,
-    line_start_text: String,
+    line: InputLine,
+    completion: Option<Completion>,
     verb_lookup: VerbLookup,
-    error: bool,
+    person_lookup: PersonLookup,
+    direction_lookup: DirectionLookup,
+    pending: Option<(String, String)>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Parser {
     pub fn new() -> Self {
-        let mut paint = Paint::default();
-        paint.set_style(PaintStyle::StrokeAndFill);
-        paint.set_anti_alias(true);
-        paint.set_color(Color::YELLOW);
         Parser {
-            current_line: String::new(),
-            paint,
-            line_start_text: "# ".to_string(),
+            line: InputLine::new(),
+            completion: None,
             verb_lookup: VerbLookup::new(),
-            error: false,
+            person_lookup: PersonLookup::new(),
+            direction_lookup: DirectionLookup::new(),
+            pending: None,
         }
     }
 
+    pub fn process_key(&mut self, text: String) {
+        self.line.insert(&text);
+        self.completion = None;
+    }
+
+    pub fn process_backspace(&mut self) {
+        self.completion = None;
+        self.line.backspace();
+    }
+
+    /// Anything else done to the line, e.g. moving the caret or recalling an earlier command.
+    pub fn edit_line(&mut self) -> &mut InputLine {
+        self.completion = None;
+        &mut self.line
+    }
+
+    /// Finish the name before the caret, or swap it for the next candidate if Tab was pressed already.
+    pub fn process_tab(&mut self, game_state: &GameState) {
+        let completion = match self.completion.take() {
+            Some(completion) => Completion {
+                index: (completion.index + 1) % completion.candidates.len(),
+                ..completion
+            },
+            None => {
+                let (start, candidates) = self.completions(game_state);
+                if candidates.is_empty() {
+                    return;
+                }
+                Completion {
+                    start,
+                    candidates,
+                    index: 0,
+                }
+            }
+        };
+        self.line.replace_word(completion.start, &completion.candidates[completion.index]);
+        self.completion = Some(completion);
+    }
+
+    /// Where the name before the caret starts, and the names it could be. Verbs and directions start a
+    /// command, after that it's people in reach and whatever is at hand. Names can be more than one word, so
+    /// the longest run of typed words that starts one wins, e.g. "OXYGEN G" finishes as "OXYGEN GAUGE".
+    fn completions(&self, game_state: &GameState) -> (usize, Vec<String>) {
+        let before = self.line.before_caret();
+        let last = before.rfind(' ').map(|index| index + 1).unwrap_or(0);
+        if last == before.len() || self.line.text().len() != before.len() {
+            return (last, Vec::new());
+        }
+
+        let starts = std::iter::once(0).chain(before.match_indices(' ').map(|(index, _)| index + 1));
+        for start in starts.filter(|start| !before[*start..].starts_with(' ')) {
+            let typed = before[start..].to_uppercase();
+            let mut vocabulary = self.direction_lookup.words();
+            if before[..start].trim().is_empty() {
+                vocabulary.extend(self.verb_lookup.words());
+            } else {
+                vocabulary.extend(self.person_lookup.words(game_state));
+                vocabulary.extend(game_state.visible_names());
+            }
+            let mut candidates = vocabulary
+                .into_iter()
+                .map(|candidate| candidate.to_uppercase())
+                .filter(|candidate| candidate.starts_with(&typed) && !FILLER_WORDS.contains(&candidate.as_str()))
+                .collect::<Vec<String>>();
+            if !candidates.is_empty() {
+                candidates.sort();
+                candidates.dedup();
+                return (start, candidates);
+            }
+        }
+        (last, Vec::new())
+    }
+
+    /// Enter a whole line at once, as if typed and followed by return.
+    pub fn submit(&mut self, line: &str, game_state: &mut GameState, printer: &mut Printer) {
+        self.line.set(line);
+        self.completion = None;
+        self.process_enter(game_state, printer);
+    }
+
+    pub fn process_enter(&mut self, game_state: &mut GameState, printer: &mut Printer) {
+        let line = self.line.text().trim().to_uppercase();
+        if line.is_empty() {
+            return;
+        }
+        self.line.remember();
+        self.completion = None;
+
+        // Settings aren't part of the game, so work even mid-conversation
+        if let Some(rest) = line.strip_prefix("SPEED").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            self.echo(&line, printer);
+            match TextSpeed::find(rest.trim()) {
+                Some(speed) => {
+                    printer.text_speed = speed;
+                    printer.print(&format!("Text speed is now {}.", speed.name()), PrintStyle::Normal);
+                }
+                None => printer.print(
+                    &format!("Text speed is {}. Try SPEED SLOW, NORMAL, FAST or INSTANT.", printer.text_speed.name()),
+                    PrintStyle::Normal,
+                ),
+            }
+            self.line.clear();
+            return;
+        }
+        if let Some(rest) = line.strip_prefix("DIFFICULTY").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            self.echo(&line, printer);
+            match Difficulty::find(rest.trim()) {
+                Some(difficulty) => {
+                    game_state.difficulty = difficulty;
+                    printer.print(
+                        &format!("Difficulty is now {}, {}.", difficulty.name(), difficulty.describe()),
+                        PrintStyle::Normal,
+                    );
+                }
+                None => printer.print(
+                    &format!(
+                        "Difficulty is {}, {}. Try DIFFICULTY STORY, NORMAL or HARD.",
+                        game_state.difficulty.name(),
+                        game_state.difficulty.describe()
+                    ),
+                    PrintStyle::Normal,
+                ),
+            }
+            self.line.clear();
+            return;
+        }
+
+        // Once the story has ended only a saved game can carry it on
+        if game_state.game_over
+            && !matches!(
+                self.parse_command(&line, game_state),
+                Ok(Command {
+                    verb: Verbs::Restore,
+                    ..
+                })
+            )
+        {
+            self.echo(&line, printer);
+            printer.print("Your story has ended. Type RESTORE to go back to your saved game.", PrintStyle::Normal);
+            self.line.clear();
+            return;
+        }
+
+        // Mid-conversation everything typed picks a dialogue option
+        if game_state.in_conversation() {
+            self.echo(&line, printer);
+            game_state.choose_option(&line, printer);
+            self.line.clear();
+            return;
+        }
+
+        // Answering a "Which do you mean" question? Slot the answer into the earlier command
+        let mut result = self.parse_command(&line, game_state);
+        if let Some((before, after)) = self.pending.take() {
+            if matches!(result, Err(ParseError::UnknownVerb(_))) {
+                result = self.parse_command(&format!("{} {} {}", before, line, after), game_state);
+            }
+        }
+
+        self.echo(&line, printer);
+        self.line.clear();
+        match result {
+            Ok(command) => game_state.handle(&command, printer),
+            Err(ParseError::Ambiguous {
+                candidates,
+                before,
+                after,
+            }) => {
+                let names = candidates
+                    .iter()
+                    .map(|id| format!("the {}", game_state.items.get(*id).name))
+                    .collect::<Vec<String>>();
+                let (last, rest) = names.split_last().unwrap();
+                printer.print(&format!("Which do you mean, {} or {}?", rest.join(", "), last), PrintStyle::Normal);
+                self.pending = Some((before, after));
+            }
+            Err(ParseError::UnknownVerb(word)) => {
+                let suggestion = match self.verb_lookup.suggest(&word) {
+                    Some(verb) => format!(" Did you mean {}?", verb),
+                    None => String::new(),
+                };
+                printer
+                    .print(&format!("I don't know the word \"{}\".{}", escape(&word), suggestion), PrintStyle::Normal);
+            }
+            Err(ParseError::UnknownNoun(phrase)) => {
+                printer.print(&format!("I don't know what \"{}\" is.", escape(&phrase)), PrintStyle::Normal)
+            }
+            Err(ParseError::NotHere(phrase)) => {
+                printer.print(&format!("There's no {} here.", escape(&phrase.to_lowercase())), PrintStyle::Normal)
+            }
+            Err(ParseError::Unfinished) => printer.print(&format!("{} what?", escape(&line)), PrintStyle::Normal),
+        }
+    }
+
+    fn echo(&self, line: &str, printer: &mut Printer) {
+        printer.print(&format!("<b>{}</b>\n", escape(line)), PrintStyle::Normal);
+    }
+
+    fn parse_command(&self, line: &str, game_state: &GameState) -> Result<Command, ParseError> {
+        let split = line.split_whitespace().collect::<Vec<&str>>();
+
+        // A bare direction is short for GO, e.g. NORTH or IN
+        if let [word] = split.as_slice() {
+            if let Some(direction) = self.direction_lookup.find_direction(word) {
+                return Ok(Command {
+                    verb: Verbs::Go,
+                    object: Some(Noun::Exit(*direction)),
+                    preposition: None,
+                    indirect: None,
+                });
+            }
+        }
+
+        // Search for verb, two words first for the likes of PICK UP
+        let first = split.first().unwrap_or(&"");
+        let (verb, mut start) = match split.get(..2).and_then(|words| self.verb_lookup.find_verb(&words.join(" "))) {
+            Some(verb) => (*verb, 2),
+            None => (*self.verb_lookup.find_verb(first).ok_or(ParseError::UnknownVerb(first.to_string()))?, 1),
+        };
+
+        // Skip a leading preposition, e.g. TALK TO CENTRAL or LOOK AT HELMET, but not GO IN
+        if split.len() > start + 1 && Preposition::find(split[start]).is_some() {
+            start += 1;
+        }
+
+        // Direct object, then optional preposition and indirect object
+        let (direct, preposition, indirect) =
+            match (start + 1..split.len()).find(|index| Preposition::find(split[*index]).is_some()) {
+                Some(index) => (start..index, Preposition::find(split[index]), index + 1..split.len()),
+                None => (start..split.len(), None, split.len()..split.len()),
+            };
+        if preposition.is_some() && indirect.is_empty() {
+            return Err(ParseError::Unfinished);
+        }
+
+        let object = self.find_noun(&split, direct, game_state)?;
+        let indirect = self.find_noun(&split, indirect, game_state)?;
+
+        Ok(Command {
+            verb,
+            object,
+            preposition,
+            indirect,
+        })
+    }
+
+    fn find_noun(
+        &self,
+        split: &[&str],
+        range: Range<usize>,
+        game_state: &GameState,
+    ) -> Result<Option<Noun>, ParseError> {
+        if range.is_empty() {
+            return Ok(None);
+        }
+        let words = split[range.clone()]
+            .iter()
+            .map(|word| word.to_uppercase())
+            .filter(|word| !FILLER_WORDS.contains(&word.as_str()))
+            .collect::<Vec<String>>();
+
+        // Only filler, e.g. TAKE THE, is as good as no object at all
+        if words.is_empty() {
+            return Ok(None);
+        }
+
+        let phrase = words.join(" ");
+        if let Some(person) = self.person_lookup.find_person(&phrase) {
+            return Ok(Some(Noun::Person(*person)));
+        }
+        if let Some(direction) = self.direction_lookup.find_direction(&phrase) {
+            return Ok(Some(Noun::Exit(*direction)));
+        }
+        if let Some(direction) = game_state.find_exit(&phrase) {
+            return Ok(Some(Noun::Exit(direction)));
+        }
+        // Prefer whatever the player can actually see
+        let mut props = game_state.items.find_props(&words);
+        if props.iter().any(|id| game_state.items.is_visible(*id, game_state.location)) {
+            props.retain(|id| game_state.items.is_visible(*id, game_state.location));
+        }
+        match props.len() {
+            // A way out somewhere else, e.g. the lab door from outside
+            0 if game_state
+                .locations
+                .values()
+                .flat_map(|location| location.exits.iter())
+                .any(|exit| exit.matches(&phrase)) =>
+            {
+                Err(ParseError::NotHere(phrase))
+            }
+            0 => Err(ParseError::UnknownNoun(phrase)),
+            1 => Ok(Some(Noun::Prop(props[0]))),
+            _ => Err(ParseError::Ambiguous {
+                candidates: props,
+                before: split[..range.start].join(" "),
+                after: split[range.end..].join(" "),
+            }),
+        }
+    }
+}
+
+/// Drawing the prompt, only in the window.
+#[cfg(feature = "gui")]
+impl Parser {
     fn get_full_text(&self) -> String {
-        let t = self.line_start_text.as_str().to_owned() + self.current_line.as_str();
-        t
+        PROMPT.to_owned() + self.line.text()
     }
 
-    pub fn print(&self, skia: &mut Skia, printer: &mut Printer) {
-        self.calc_cursor(skia, printer);
+    pub fn print(&self, skia: &mut Skia, screen: &mut Screen, game_state: &GameState) {
+        self.calc_cursor(skia, screen);
         let canvas = skia.surface.canvas();
         canvas.draw_text_align(
             self.get_full_text(),
-            Point::new(printer.padding, printer.cursor.y),
+            Point::new(screen.padding, screen.prompt_y()),
             &skia.font_main_bold,
-            &self.paint,
+            &screen.prompt_paint,
             Align::Left,
         );
 
-        // Error?
-        if self.error {
+        // Greyed out rest of the first name that would complete the one being typed
+        let (start, candidates) = self.completions(game_state);
+        let typed = self.line.text()[start..].to_uppercase().len();
+        if let Some(rest) = candidates.first().filter(|_| self.completion.is_none()).map(|word| &word[typed..]) {
+            let mut paint = screen.prompt_paint.clone();
+            paint.set_color(Color::GRAY);
             canvas.draw_text_align(
-                "Sorry, I don't understand what you mean.",
-                Point::new(printer.padding, printer.cursor.y + FONT_SIZE * 2.5),
-                &skia.font_main,
-                &self.paint,
+                rest,
+                Point::new(screen.cursor.x, screen.prompt_y()),
+                &skia.font_main_bold,
+                &paint,
                 Align::Left,
             );
         }
     }
 
-    fn calc_cursor(&self, skia: &mut Skia, printer: &mut Printer) {
-        let p = skia.font_main_bold.measure_text(self.get_full_text(), Some(&self.paint));
-        printer.cursor.x = p.0 + printer.padding;
-    }
-
-    pub fn process_key(&mut self, text: String) {
-        self.current_line.push_str(&text);
-        self.error = false;
-    }
-
-    pub fn process_backspace(&mut self) {
-        self.error = false;
-        if self.current_line.is_empty() {
-            return;
-        }
-        self.current_line.pop();
-    }
-
-    pub fn process_enter(&mut self, game_state: &mut GameState, printer: &mut Printer) {
-        let split = self.current_line.split(" ").collect::<Vec<&str>>();
-
-        // Search for verb
-        let verb = self.verb_lookup.find_verb(&split[0].to_string());
-        if let Some(verb) = verb {
-            let a = 1;
-        } else {
-            self.error = true;
-        }
+    fn calc_cursor(&self, skia: &mut Skia, screen: &mut Screen) {
+        let before = PROMPT.to_owned() + self.line.before_caret();
+        let p = skia.font_main_bold.measure_text(before, Some(&screen.prompt_paint));
+        screen.cursor.x = p.0 + screen.padding;
     }
 }
@@ -14,10 +14,15 @@ impl VerbLookup {
         lookups.insert("DROP".to_string(), Verbs::Drop);
         lookups.insert("LEAVE".to_string(), Verbs::Drop);
         lookups.insert("DISCARD".to_string(), Verbs::Drop);
+        lookups.insert("PUT".to_string(), Verbs::Drop);
 
         lookups.insert("GIVE".to_string(), Verbs::Give);
         lookups.insert("OFFER".to_string(), Verbs::Give);
 
+        lookups.insert("INVENTORY".to_string(), Verbs::Inventory);
+        lookups.insert("INV".to_string(), Verbs::Inventory);
+        lookups.insert("I".to_string(), Verbs::Inventory);
+
         lookups.insert("LOOK".to_string(), Verbs::Look);
         lookups.insert("EXAMINE".to_string(), Verbs::Look);
         lookups.insert("INSPECT".to_string(), Verbs::Look);
@@ -31,12 +36,18 @@ impl VerbLookup {
         lookups.insert("OPEN".to_string(), Verbs::Open);
         lookups.insert("UNLOCK".to_string(), Verbs::Open);
 
+        lookups.insert("RESTORE".to_string(), Verbs::Restore);
+        lookups.insert("LOAD".to_string(), Verbs::Restore);
+
         lookups.insert("RUN".to_string(), Verbs::Run);
         lookups.insert("DASH".to_string(), Verbs::Run);
         lookups.insert("SPRINT".to_string(), Verbs::Run);
 
+        lookups.insert("SAVE".to_string(), Verbs::Save);
+
         lookups.insert("TAKE".to_string(), Verbs::Take);
         lookups.insert("PICKUP".to_string(), Verbs::Take);
+        lookups.insert("PICK UP".to_string(), Verbs::Take);
         lookups.insert("GRAB".to_string(), Verbs::Take);
 
         lookups.insert("TALK".to_string(), Verbs::Talk);
@@ -53,7 +64,56 @@ impl VerbLookup {
         }
     }
 
-    pub fn find_verb(&self, key: &String) -> Option<&Verbs> {
+    pub fn find_verb(&self, key: &str) -> Option<&Verbs> {
         self.lookups.get(&key.to_uppercase())
     }
+
+    /// The closest verb to a misspelt one, if any is near enough to be a likely typo.
+    pub fn suggest(&self, word: &str) -> Option<&str> {
+        let word = word.to_uppercase();
+        let allowed = if word.chars().count() <= 4 {
+            1
+        } else {
+            2
+        };
+        self.lookups
+            .keys()
+            .map(|key| (edit_distance(&word, key), key.as_str()))
+            .filter(|(distance, _)| *distance <= allowed)
+            .min()
+            .map(|(_, key)| key)
+    }
+
+    /// Every verb with its synonyms, offered when completing the first word of a command.
+    pub fn words(&self) -> Vec<&str> {
+        self.lookups.keys().map(String::as_str).collect()
+    }
+}
+
+/// How many letters have to be added, removed, changed or swapped with their neighbour to turn one word into the
+/// other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] {
+                0
+            } else {
+                1
+            };
+            let mut distance =
+                (distances[i - 1][j] + 1).min(distances[i][j - 1] + 1).min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
 }
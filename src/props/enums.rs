@@ -1,9 +1,12 @@
-pub enum PropsID {
-    Helmet,
-    SuitOxygenSensor,
-}
+use crate::content_id;
+use serde::Deserialize;
+
+content_id!(PropsID);
 
-pub  enum PropTypes {
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PropTypes {
     Fixed,
     Interactable,
+    Container,
 }
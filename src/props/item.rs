@@ -1,6 +1,26 @@
+use crate::game::effects::Effect;
 use crate::props::enums::{PropTypes, PropsID};
+use crate::props::placement::Placement;
 
 pub struct Item {
     pub props_id: PropsID,
+    pub name: String,
+    pub description: String,
     pub types: Vec<PropTypes>,
+    pub start: Placement,
+    pub names: Vec<String>,
+    pub usage: Option<Usage>,
+}
+
+/// What using the item on its own does, like the oxygen station refilling the suit.
+#[derive(Debug, Clone)]
+pub struct Usage {
+    pub text: String,
+    pub effects: Vec<Effect>,
+}
+
+impl Item {
+    pub fn is(&self, prop_type: PropTypes) -> bool {
+        self.types.contains(&prop_type)
+    }
 }
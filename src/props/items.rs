@@ -1,28 +1,97 @@
-use crate::props::enums::{PropTypes, PropsID};
+use crate::location::locations::LocationID;
+use crate::props::enums::PropsID;
 use crate::props::item::Item;
+use crate::props::placement::Placement;
 use crate::props::prop_lookup::PropLookup;
+use std::collections::HashMap;
 
 pub struct Items {
     items: Vec<Item>,
     prop_lookup: PropLookup,
+    placements: HashMap<PropsID, Placement>,
 }
 
 impl Items {
-    pub fn new() -> Items {
-        let items = vec![
-            Item {
-                props_id: PropsID::Helmet,
-                types: vec![PropTypes::Interactable],
-            },
-            Item {
-                props_id: PropsID::SuitOxygenSensor,
-                types: vec![PropTypes::Interactable],
-            },
-        ];
+    pub fn new(items: Vec<Item>) -> Items {
+        let placements = items.iter().map(|item| (item.props_id, item.start)).collect();
+        let prop_lookup = PropLookup::new(&items);
 
         Items {
             items,
-            prop_lookup: PropLookup::new(),
+            prop_lookup,
+            placements,
         }
     }
+
+    pub fn get(&self, id: PropsID) -> &Item {
+        self.items.iter().find(|item| item.props_id == id).unwrap()
+    }
+
+    pub fn placement(&self, id: PropsID) -> Placement {
+        self.placements.get(&id).copied().unwrap_or(Placement::Nowhere)
+    }
+
+    pub fn placements(&self) -> Vec<(PropsID, Placement)> {
+        self.items.iter().map(|item| (item.props_id, self.placement(item.props_id))).collect()
+    }
+
+    pub fn place(&mut self, id: PropsID, placement: Placement) {
+        self.placements.insert(id, placement);
+    }
+
+    /// Carried by the player, either in hand or inside something they carry.
+    pub fn is_carried(&self, id: PropsID) -> bool {
+        match self.placement(id) {
+            Placement::Player => true,
+            Placement::Inside(container) => self.is_carried(container),
+            _ => false,
+        }
+    }
+
+    /// Whether the item is the container, or holds it somewhere inside, so putting it there would make a loop.
+    pub fn is_around(&self, id: PropsID, container: PropsID) -> bool {
+        match self.placement(container) {
+            _ if id == container => true,
+            Placement::Inside(outer) => self.is_around(id, outer),
+            _ => false,
+        }
+    }
+
+    /// Whether the player can see the item from the given location.
+    pub fn is_visible(&self, id: PropsID, location: LocationID) -> bool {
+        match self.placement(id) {
+            Placement::Player => true,
+            Placement::Location(at) => at == location,
+            Placement::Inside(container) => self.is_visible(container, location),
+            Placement::Person(_) | Placement::Nowhere => false,
+        }
+    }
+
+    pub fn give_player(&mut self, id: PropsID) {
+        self.place(id, Placement::Player);
+    }
+
+    pub fn take_from_player(&mut self, id: PropsID) {
+        self.place(id, Placement::Nowhere);
+    }
+
+    pub fn carried(&self) -> Vec<PropsID> {
+        self.placed(Placement::Player)
+    }
+
+    pub fn at_location(&self, id: LocationID) -> Vec<PropsID> {
+        self.placed(Placement::Location(id))
+    }
+
+    pub fn inside(&self, id: PropsID) -> Vec<PropsID> {
+        self.placed(Placement::Inside(id))
+    }
+
+    fn placed(&self, placement: Placement) -> Vec<PropsID> {
+        self.items.iter().map(|item| item.props_id).filter(|id| self.placement(*id) == placement).collect()
+    }
+
+    pub fn find_props(&self, words: &[String]) -> Vec<PropsID> {
+        self.prop_lookup.find_props(words)
+    }
 }
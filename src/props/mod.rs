@@ -1,4 +1,5 @@
-mod enums;
-mod item;
+pub mod enums;
+pub mod item;
 pub mod items;
+pub mod placement;
 mod prop_lookup;
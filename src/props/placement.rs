@@ -0,0 +1,15 @@
+use crate::dialogue::logic::DialoguePersonID;
+use crate::location::locations::LocationID;
+use crate::props::enums::PropsID;
+use serde::{Deserialize, Serialize};
+
+/// Where an item currently is.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Placement {
+    Nowhere,
+    Location(LocationID),
+    Player,
+    Person(DialoguePersonID),
+    Inside(PropsID),
+}
@@ -1,4 +1,5 @@
 use crate::props::enums::PropsID;
+use crate::props::item::Item;
 use std::collections::HashMap;
 
 pub struct PropLookup {
@@ -6,13 +7,49 @@ pub struct PropLookup {
 }
 
 impl PropLookup {
-    pub fn new() -> PropLookup {
+    pub fn new(items: &[Item]) -> PropLookup {
         let mut lookups = HashMap::new();
-        lookups.insert("HELMET".to_string(), PropsID::Helmet);
-        lookups.insert("SUIT OXYGEN SENSOR".to_string(), PropsID::SuitOxygenSensor);
+        for item in items.iter() {
+            for name in item.names.iter() {
+                lookups.insert(name.to_uppercase(), item.props_id);
+            }
+        }
 
         PropLookup {
             lookups,
         }
     }
+
+    /// Longest-match resolution of a noun phrase. The longest run of words that names at least one prop
+    /// wins; an exact name beats partial ones, so "SENSOR" or "OXYGEN SENSOR" both find the suit oxygen
+    /// sensor. Several results mean the phrase is ambiguous.
+    pub fn find_props(&self, words: &[String]) -> Vec<PropsID> {
+        for length in (1..=words.len()).rev() {
+            for start in 0..=(words.len() - length) {
+                let found = self.match_span(&words[start..start + length]);
+                if !found.is_empty() {
+                    return found;
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn match_span(&self, span: &[String]) -> Vec<PropsID> {
+        if let Some(id) = self.lookups.get(&span.join(" ").to_uppercase()) {
+            return vec![*id];
+        }
+
+        // Partial names, the words have to appear in order but others can be left out
+        let mut found = Vec::new();
+        for (key, id) in self.lookups.iter() {
+            let mut key_words = key.split(' ');
+            let matches = span.iter().all(|word| key_words.any(|key_word| key_word.eq_ignore_ascii_case(word)));
+            if matches && !found.contains(id) {
+                found.push(*id);
+            }
+        }
+        found.sort();
+        found
+    }
 }
@@ -0,0 +1,389 @@
+use crate::app_state::GFXState;
+use crate::markup::{QueueItem, Run};
+use crate::printer::{PrintStyle, Printer, TextSpeed};
+use crate::skia::Skia;
+use skia_safe::paint::Style;
+use skia_safe::utils::text_utils::Align;
+use skia_safe::{Color, Font, Paint, PaintStyle, Point, Rect};
+use std::collections::HashMap;
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub struct PrinterStyle {
+    pub paint: Paint,
+    pub font: Font,
+    pub font_bold: Font,
+    pub font_italic: Font,
+    pub font_bold_italic: Font,
+}
+
+impl PrinterStyle {
+    fn new(paint: Paint, font: &Font, font_bold: &Font) -> PrinterStyle {
+        // Slanted rather than a true italic face, good enough for emphasis
+        let slant = |font: &Font| {
+            let mut font = font.clone();
+            font.set_skew_x(-0.2);
+            font
+        };
+        PrinterStyle {
+            paint,
+            font: font.clone(),
+            font_bold: font_bold.clone(),
+            font_italic: slant(font),
+            font_bold_italic: slant(font_bold),
+        }
+    }
+
+    fn font(&self, bold: bool, italic: bool) -> &Font {
+        match (bold, italic) {
+            (false, false) => &self.font,
+            (true, false) => &self.font_bold,
+            (false, true) => &self.font_italic,
+            (true, true) => &self.font_bold_italic,
+        }
+    }
+}
+
+struct OnScreenWord {
+    pos: Point,
+    c: String,
+    style: Arc<PrinterStyle>,
+    is_bold: bool,
+    is_italic: bool,
+    paint: Option<Paint>,
+    link: Option<String>,
+    width: f32,
+}
+
+/// How much of the queue to reveal at once when the player skips ahead.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+enum Flush {
+    Nothing,
+    Paragraph,
+    Everything,
+}
+
+/// The printer's text laid out in the window and revealed a word at a time.
+pub struct Screen {
+    pub cursor: Point,
+    onscreen: Vec<OnScreenWord>,
+    pub(crate) padding: f32,
+    v_adjust: f32,
+    next_time: Instant,
+    pub style: HashMap<PrintStyle, Arc<PrinterStyle>>,
+    pub(crate) prompt_paint: Paint,
+    placed: Vec<QueueItem>,
+    flush: Flush,
+    skipped: bool,
+    scrollback: f32,
+    text_speed: TextSpeed,
+}
+
+// Milliseconds to reveal each character at normal speed, and the gap after a line break or paragraph
+const CHAR_MILLIS: f32 = 20.0;
+const BREAK_MILLIS: f32 = 32.0 * CHAR_MILLIS;
+
+impl Screen {
+    pub fn new(skia: &Skia) -> Screen {
+        let mut paint_white = Paint::default();
+        paint_white.set_anti_alias(true);
+        paint_white.set_style(Style::StrokeAndFill);
+        paint_white.set_color(Color::WHITE);
+        let padding = 80.0f32;
+
+        // Styles
+        let mut map = HashMap::new();
+        map.insert(
+            PrintStyle::Normal,
+            Arc::new(PrinterStyle::new(paint_white.clone(), &skia.font_main, &skia.font_main_bold)),
+        );
+        paint_white.set_color(Color::MAGENTA);
+        map.insert(
+            PrintStyle::Echo,
+            Arc::new(PrinterStyle::new(paint_white.clone(), &skia.font_echo, &skia.font_echo)),
+        );
+        paint_white.set_color(Color::MAGENTA);
+        map.insert(PrintStyle::AI, Arc::new(PrinterStyle::new(paint_white, &skia.font_ai, &skia.font_ai_bold)));
+
+        let mut prompt_paint = Paint::default();
+        prompt_paint.set_style(PaintStyle::StrokeAndFill);
+        prompt_paint.set_anti_alias(true);
+        prompt_paint.set_color(Color::YELLOW);
+
+        Screen {
+            onscreen: Vec::new(),
+            padding,
+            v_adjust: 120.0,
+            cursor: Point::new(padding, padding),
+            next_time: Instant::now(),
+            style: map,
+            prompt_paint,
+            placed: Vec::new(),
+            flush: Flush::Nothing,
+            skipped: false,
+            scrollback: 0.0,
+            text_speed: TextSpeed::Normal,
+        }
+    }
+
+    /// Lay out everything shown so far again, after the window changes size. Text still to come carries on.
+    pub fn reflow(&mut self, gfx: &GFXState) {
+        self.onscreen.clear();
+        self.cursor = Point::new(self.padding, self.padding);
+        self.scrollback = 0.0;
+        for item in self.placed.clone() {
+            self.lay_out(item, gfx);
+        }
+        self.next_time = Instant::now();
+    }
+
+    /// The player wants to get on. The first press finishes the paragraph being revealed, the next the lot.
+    pub fn skip(&mut self, printer: &Printer) {
+        if !printer.is_writing() {
+            return;
+        }
+        self.flush = if self.skipped {
+            Flush::Everything
+        } else {
+            Flush::Paragraph
+        };
+        self.skipped = true;
+    }
+
+    /// Where the prompt goes, which moves down off the screen when the player scrolls back.
+    pub fn prompt_y(&self) -> f32 {
+        self.cursor.y + self.scrollback
+    }
+
+    pub fn is_scrolled_back(&self) -> bool {
+        self.scrollback > 0.0
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scrollback = 0.0;
+    }
+
+    /// Scroll back through earlier text by a number of lines, or forward when negative.
+    pub fn scroll_lines(&mut self, lines: i32) {
+        let line = self.style.get(&PrintStyle::Normal).unwrap().font.size() * 1.25;
+        self.scroll_by(lines as f32 * line);
+    }
+
+    /// As scroll_lines, a screenful at a time.
+    pub fn scroll_pages(&mut self, pages: i32, gfx: &GFXState) {
+        self.scroll_by(pages as f32 * (gfx.height as f32 - self.padding * 2.0));
+    }
+
+    fn scroll_by(&mut self, amount: f32) {
+        // No further back than the first word at the top of the screen
+        let top = self.onscreen.first().map(|osw| self.padding - osw.pos.y).unwrap_or(0.0);
+        self.scrollback = (self.scrollback + amount).clamp(0.0, top.max(0.0));
+    }
+
+    fn check_for_vertical_scroll(&mut self, gfx: &GFXState) {
+        while self.cursor.y > (gfx.height as f32 - self.padding) {
+            // Words scrolled off the top are kept for scrolling back to
+            self.onscreen.iter_mut().for_each(|screen| {
+                screen.pos.y -= self.v_adjust;
+            });
+
+            // Adjust cursor
+            self.cursor.y -= self.v_adjust;
+        }
+    }
+
+    fn single_linefeed(&mut self, style: Arc<PrinterStyle>, gfx: &GFXState) {
+        self.cursor.y += style.font.size() * 1.25;
+        self.check_for_vertical_scroll(gfx);
+    }
+
+    fn double_linefeed(&mut self, style: Arc<PrinterStyle>, gfx: &GFXState) {
+        self.cursor.y += style.font.size() * 2.5;
+        self.check_for_vertical_scroll(gfx);
+    }
+
+    /// Lay out the next item from the printer, remembering it in case the window changes size.
+    fn place(&mut self, item: QueueItem, printer: &Printer, gfx: &GFXState) {
+        let is_word = matches!(item, QueueItem::Word(_));
+        self.placed.push(item.clone());
+        self.lay_out(item, gfx);
+
+        // Last one, leave a gap before whatever comes next
+        if is_word && !printer.is_writing() {
+            self.lay_out(QueueItem::Paragraph, gfx);
+            self.placed.push(QueueItem::Paragraph);
+        }
+    }
+
+    fn lay_out(&mut self, item: QueueItem, gfx: &GFXState) {
+        let normal = self.style.get(&PrintStyle::Normal).unwrap().clone();
+        match item {
+            QueueItem::Paragraph => {
+                self.cursor.x = self.padding;
+                self.double_linefeed(normal, gfx);
+                self.wait(BREAK_MILLIS);
+            }
+            QueueItem::LineBreak => {
+                self.cursor.x = self.padding;
+                self.single_linefeed(normal, gfx);
+                self.wait(BREAK_MILLIS);
+            }
+            QueueItem::Pause(millis) => self.wait(millis as f32),
+            QueueItem::Word(runs) => {
+                let style = self.style.get(&runs[0].format.style).unwrap().clone();
+
+                // Size the whole word, so a word changing format part way through isn't split over two lines
+                let widths = runs.iter().map(|run| self.measure(run, "")).collect::<Vec<f32>>();
+                let space = self.measure(runs.last().unwrap(), " ") - widths.last().unwrap();
+                let width = widths.iter().sum::<f32>() + space;
+
+                // Move cursor down?
+                if (self.cursor.x + width) > (gfx.width as f32 - self.padding) {
+                    self.cursor.x = self.padding;
+                    self.single_linefeed(style, gfx);
+                }
+
+                let length = runs.iter().map(|run| run.text.len()).sum::<usize>() + 1;
+                let speed = runs[0].format.speed;
+                for (run, width) in runs.into_iter().zip(widths) {
+                    let paint = run.format.color.map(|(r, g, b)| {
+                        let mut paint = self.style.get(&run.format.style).unwrap().paint.clone();
+                        paint.set_color(Color::from_rgb(r, g, b));
+                        paint
+                    });
+                    self.onscreen.push(OnScreenWord {
+                        pos: self.cursor,
+                        c: run.text,
+                        style: self.style.get(&run.format.style).unwrap().clone(),
+                        is_bold: run.format.bold,
+                        is_italic: run.format.italic,
+                        paint,
+                        link: run.format.link,
+                        width,
+                    });
+                    self.cursor.x += width;
+                }
+                self.cursor.x += space;
+
+                // Delay for next word, <speed=2> reveals it twice as fast
+                self.wait(CHAR_MILLIS * length as f32 / speed);
+            }
+        }
+    }
+
+    /// Hold back the next item, scaled by the player's text speed.
+    fn wait(&mut self, millis: f32) {
+        let millis = millis * self.text_speed.scale();
+        self.next_time = Instant::now().add(Duration::from_millis(millis as u64));
+    }
+
+    fn measure(&self, run: &Run, suffix: &str) -> f32 {
+        let style = self.style.get(&run.format.style).unwrap();
+        let text = run.text.clone() + suffix;
+        style.font(run.format.bold, run.format.italic).measure_text(&text, Some(&style.paint)).0
+    }
+
+    /// The command behind a link at the given point on screen, if any.
+    pub fn link_at(&self, point: Point) -> Option<String> {
+        if point.y < self.padding {
+            return None;
+        }
+        self.onscreen.iter().find_map(|osw| {
+            let font = osw.style.font(osw.is_bold, osw.is_italic);
+            let y = osw.pos.y + self.scrollback;
+            let rect = Rect::from_xywh(osw.pos.x, y - font.size(), osw.width, font.size() * 1.25);
+            osw.link.clone().filter(|_| rect.contains(point))
+        })
+    }
+
+    pub fn print_render(&mut self, printer: &mut Printer, skia: &mut Skia, gfx: &GFXState, phase: f32) {
+        self.text_speed = printer.text_speed;
+
+        // A restored game starts the screen again, laid out without waiting
+        if printer.take_restored() {
+            self.onscreen.clear();
+            self.placed.clear();
+            self.cursor = Point::new(self.padding, self.padding);
+            self.flush = Flush::Everything;
+        }
+        if printer.take_printed() {
+            self.scroll_to_bottom();
+        }
+
+        // Skipping lays out text without waiting too
+        if self.flush != Flush::Nothing {
+            while let Some(c) = printer.next_item() {
+                let end = c == QueueItem::Paragraph && self.flush == Flush::Paragraph;
+                self.place(c, printer, gfx);
+                if end {
+                    break;
+                }
+            }
+            self.flush = Flush::Nothing;
+        }
+
+        // Place everything that's due, several words a frame when the text is fast
+        while Instant::now() >= self.next_time {
+            match printer.next_item() {
+                Some(c) => self.place(c, printer, gfx),
+                None => break,
+            }
+        }
+        if !printer.is_writing() {
+            self.skipped = false;
+        }
+
+        // Draw whatever is in view
+        let canvas = skia.surface.canvas();
+        let bottom = gfx.height as f32 + self.padding;
+        self.onscreen.iter().for_each(|osw| {
+            let pos = Point::new(osw.pos.x, osw.pos.y + self.scrollback);
+            if pos.y < self.padding || pos.y > bottom {
+                return;
+            }
+            let paint = osw.paint.as_ref().unwrap_or(&osw.style.paint);
+            canvas.draw_text_align(osw.c.as_str(), pos, osw.style.font(osw.is_bold, osw.is_italic), paint, Align::Left);
+
+            // Links are underlined
+            if osw.link.is_some() {
+                let y = pos.y + 3.0;
+                canvas.draw_line(Point::new(pos.x, y), Point::new(pos.x + osw.width, y), paint);
+            }
+        });
+
+        // Let the player know there's more below
+        if self.is_scrolled_back() {
+            let normal = self.style.get(&PrintStyle::Normal).unwrap();
+            let mut paint = normal.paint.clone();
+            paint.set_color(Color::GRAY);
+            canvas.draw_text_align(
+                "More below, PAGE DOWN to return",
+                Point::new(gfx.width as f32 - self.padding, gfx.height as f32 - self.padding / 3.0),
+                &normal.font_italic,
+                &paint,
+                Align::Right,
+            );
+        }
+
+        // Cursor
+        if phase >= 1.0 {
+            let (_, fm) = skia.font_main.metrics();
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            let x = self.cursor.x;
+            let y = self.prompt_y() + fm.descent;
+            if printer.is_writing() {
+                paint.set_color(Color::RED);
+                paint.set_style(Style::Fill);
+                let rect = Rect::from_xywh(x, y, fm.avg_char_width / 6.0, fm.ascent);
+                canvas.draw_rect(rect, &paint);
+            } else {
+                paint.set_color(Color::GREEN);
+                paint.set_style(Style::Stroke);
+                paint.set_stroke_width(1.0);
+                canvas.draw_line(Point::new(x, y - 2.0), Point::new(x, y + fm.ascent + 2.0), &paint);
+            }
+        }
+    }
+}
@@ -6,7 +6,10 @@ use skia_safe::gpu::surfaces::wrap_backend_render_target;
 use skia_safe::gpu::{ContextOptions, DirectContext};
 use skia_safe::image_filters::drop_shadow_only;
 use skia_safe::runtime_effect::RuntimeShaderBuilder;
-use skia_safe::{gpu, Canvas, Color, Color4f, Data, Font, FontMgr, ImageFilter, Matrix, Paint, PaintStyle, Point, Rect, RuntimeEffect, Shader, Surface, Vector};
+use skia_safe::{
+    gpu, Canvas, Color, Color4f, Data, Font, FontMgr, ImageFilter, Matrix, Paint, PaintStyle, Point, Rect,
+    RuntimeEffect, Shader, Surface, Vector,
+};
 
 static AI_FONT: &[u8] = include_bytes!("../assets/NotoSansMono-Regular.ttf");
 static AI_FONT_BOLD: &[u8] = include_bytes!("../assets/NotoSansMono-Bold.ttf");
@@ -102,6 +105,11 @@ impl Skia {
         }
     }
 
+    /// The window changed size or moved to a display with a different DPI.
+    pub fn resize(&mut self, gfx: &GFXState) {
+        self.surface = Skia::make_surface(&mut self.context, gfx.width * gfx.dpi as i32, gfx.height * gfx.dpi as i32);
+    }
+
     pub fn _test(&mut self, width: i32, height: i32) {
         let canvas = self.get_canvas();
         let mut rng = rand::rng();
@@ -127,6 +135,8 @@ impl Skia {
         self.surface.canvas()
     }
 
+    /// # Safety
+    /// The GL context the surface was created on must be current.
     pub unsafe fn flush(&mut self, dpi: f32, millis: f32) {
         self.surface.image_snapshot();
         self.context.flush_and_submit();
@@ -0,0 +1,117 @@
+use crate::content::loader::Content;
+use crate::game::game_state::GameState;
+use crate::markup::{Format, QueueItem};
+use crate::parser::process::{Parser, PROMPT};
+use crate::printer::{PrintStyle, Printer};
+use std::io::{self, stdin, stdout, IsTerminal, Write};
+use std::time::Instant;
+
+const BOLD: &str = "1";
+const ITALIC: &str = "3";
+const UNDERLINE: &str = "4";
+const MAGENTA: &str = "35";
+
+/// Play the game on stdin and stdout, no window needed. Stops early if stdout goes away, e.g. piped into head.
+pub fn run(content: Content, ansi: bool) -> io::Result<()> {
+    let mut game_state = GameState::new(content);
+    let mut parser = Parser::new();
+    let mut printer = Printer::new();
+    let mut out = stdout();
+
+    game_state.start(&mut printer);
+    write!(out, "{}", render(&printer.drain(), ansi))?;
+
+    let interactive = stdin().is_terminal();
+    let mut waiting = Instant::now();
+    loop {
+        if interactive {
+            write!(out, "{}", PROMPT)?;
+        }
+        out.flush()?;
+
+        let mut line = String::new();
+        if stdin().read_line(&mut line).unwrap_or(0) == 0 {
+            return Ok(());
+        }
+
+        // On hard difficulty the time spent typing uses up oxygen too
+        game_state.pass_time(waiting.elapsed().as_secs_f32(), &mut printer);
+        write!(out, "{}", respond(line.trim_end(), &mut parser, &mut game_state, &mut printer, ansi))?;
+        waiting = Instant::now();
+    }
+}
+
+/// Run one line of input and return everything the game prints in response.
+pub fn respond(
+    line: &str,
+    parser: &mut Parser,
+    game_state: &mut GameState,
+    printer: &mut Printer,
+    ansi: bool,
+) -> String {
+    parser.submit(line, game_state, printer);
+    render(&printer.drain(), ansi)
+}
+
+/// Turn queued words into plain text, with ANSI styling for the markup if wanted.
+pub fn render(items: &[QueueItem], ansi: bool) -> String {
+    let plain = sgr(&Format::new(PrintStyle::Normal));
+    let mut current = plain.clone();
+    let mut result = String::new();
+    let mut line_start = true;
+    for item in items.iter() {
+        match item {
+            QueueItem::Paragraph => {
+                result.push_str("\n\n");
+                line_start = true;
+            }
+            QueueItem::LineBreak => {
+                result.push('\n');
+                line_start = true;
+            }
+            QueueItem::Pause(_) => {}
+            QueueItem::Word(runs) => {
+                if !line_start {
+                    result.push(' ');
+                }
+                line_start = false;
+                for run in runs.iter() {
+                    let wanted = sgr(&run.format);
+                    if ansi && wanted != current {
+                        result.push_str(&wanted);
+                        current = wanted;
+                    }
+                    result.push_str(&run.text);
+                }
+            }
+        }
+    }
+
+    if ansi && current != plain {
+        result.push_str(&plain);
+    }
+    if !result.is_empty() {
+        result.push_str("\n\n");
+    }
+    result
+}
+
+/// The escape sequence that switches the terminal to a format, starting from a reset.
+fn sgr(format: &Format) -> String {
+    let mut codes = Vec::new();
+    if format.bold {
+        codes.push(BOLD.to_string());
+    }
+    if format.italic || format.style == PrintStyle::Echo {
+        codes.push(ITALIC.to_string());
+    }
+    if format.link.is_some() {
+        codes.push(UNDERLINE.to_string());
+    }
+    match format.color {
+        Some((r, g, b)) => codes.push(format!("38;2;{};{};{}", r, g, b)),
+        None if format.style != PrintStyle::Normal => codes.push(MAGENTA.to_string()),
+        None => {}
+    }
+    format!("\x1b[0;{}m", codes.join(";"))
+}
@@ -0,0 +1,72 @@
+use selenic_echo::content::export::{export_dialogue, GraphFormat};
+use selenic_echo::content::lint::lint;
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use std::path::Path;
+
+#[test]
+fn content_loads_and_lints_clean() {
+    let content = match load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(CONTENT_DIR)) {
+        Ok(content) => content,
+        Err(e) => panic!("\n{}", e),
+    };
+    let problems = lint(&content);
+    assert!(problems.is_empty(), "\n{}", problems.join("\n"));
+}
+
+#[test]
+fn lint_finds_what_the_player_can_never_see() {
+    let content = load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/lint_content")).unwrap();
+    assert_eq!(
+        lint(&content),
+        [
+            "location \"vault\" can't be reached from \"hall\"",
+            "dialogue node \"the-end\" can't be reached from any conversation",
+            "dialogue node \"again\" only leads back round, it never reaches an ending",
+            "dialogue node \"hello\" only leads back round, it never reaches an ending",
+            "narration \"unused\" isn't used by any location or the game",
+            "item \"key\" has no names to refer to it by",
+        ]
+    );
+}
+
+#[test]
+fn mermaid_ids_stay_apart_and_labels_lose_their_markup() {
+    let content = load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/lint_content")).unwrap();
+    let graph = export_dialogue(&content, GraphFormat::Mermaid);
+    assert!(graph.contains("    n_person__central --> n_hello\n"), "{}", graph);
+    assert!(graph.contains("    n_hello[\"Central: Hello. How are you?\"]\n"), "{}", graph);
+    assert!(graph.contains("    n_the_2dend[\"Central: Nobody asks me anything.\"]\n"), "{}", graph);
+}
+
+#[test]
+fn broken_references_point_at_the_line() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/broken_content");
+    let Err(e) = load(&dir) else {
+        panic!("broken content loaded");
+    };
+    let errors = e.lines().map(|line| line.replace(&format!("{}/", dir.display()), "")).collect::<Vec<String>>();
+    assert_eq!(
+        errors,
+        [
+            "variables.toml:4: door_open can't start as 3",
+            "locations.toml:11: \"room\" has two exits north",
+            "people.toml:4: unknown location \"hall\"",
+            "people.toml:6: Watcher has no state \"waving\"",
+            "narration/start.txt:2: {if} is never closed with {end}",
+            "game.toml:5: unknown variable \"air\"",
+            "game.toml:7: unknown narration \"suffocated\"",
+            "locations.toml:4: unknown narration \"room\"",
+            "locations.toml:8: unknown location \"hall\"",
+            "items.toml:3: the name \"<i>box\" can't contain markup",
+            "items.toml:5: unknown item \"crate\"",
+            "dialogue/central.toml:7: <b> is never closed",
+            "dialogue/central.toml:11: unknown dialogue node \"goodbye\"",
+            "dialogue/central.toml:12: unknown item \"crowbar\"",
+            "dialogue/central.toml:13: unknown variable \"door_opened\"",
+            "triggers.toml:3: unknown location \"hall\"",
+            "triggers.toml:2: Watcher has no dialogue to start",
+            "triggers.toml:11: unknown item \"flag\"",
+            "triggers.toml:17: unknown location or dialogue node \"hall\"",
+        ]
+    );
+}
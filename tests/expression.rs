@@ -0,0 +1,120 @@
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use selenic_echo::dialogue::logic::{DialogueNodeID, DialoguePersonID};
+use selenic_echo::game::conditions::Condition;
+use selenic_echo::game::expression::{parse_condition, Names};
+use selenic_echo::game::game_state::GameState;
+use selenic_echo::game::variables::{Value, VariableType};
+use selenic_echo::location::locations::LocationID;
+use selenic_echo::props::enums::PropsID;
+use std::path::Path;
+
+/// Names from the real content, so conditions can be checked against a real game.
+struct GameNames<'a>(&'a GameState, Vec<(&'static str, VariableType)>, Vec<(DialoguePersonID, VariableType)>);
+
+impl Names for GameNames<'_> {
+    fn is_location(&self, id: LocationID) -> bool {
+        self.0.locations.contains_key(&id)
+    }
+
+    fn is_item(&self, id: PropsID) -> bool {
+        self.0.items.placements().iter().any(|(item, _)| *item == id)
+    }
+
+    fn is_node(&self, id: DialogueNodeID) -> bool {
+        self.0.dialogues.contains_key(&id)
+    }
+
+    fn variable(&self, name: &str) -> Option<&VariableType> {
+        self.1.iter().find(|(variable, _)| *variable == name).map(|(_, kind)| kind)
+    }
+
+    fn person(&self, id: DialoguePersonID) -> Option<&VariableType> {
+        self.2.iter().find(|(person, _)| *person == id).map(|(_, states)| states)
+    }
+}
+
+fn game() -> GameState {
+    GameState::new(load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(CONTENT_DIR)).unwrap())
+}
+
+fn variables() -> Vec<(&'static str, VariableType)> {
+    vec![
+        ("lab_unlocked", VariableType::Bool),
+        ("oxygen_percent", VariableType::Int),
+        ("central_trust", VariableType::Enum(["hostile", "wary", "neutral", "trusting"].map(String::from).to_vec())),
+    ]
+}
+
+fn people() -> Vec<(DialoguePersonID, VariableType)> {
+    vec![
+        (DialoguePersonID::Central, VariableType::Enum(Vec::new())),
+        (DialoguePersonID::Fixer, VariableType::Enum(["standby", "escorting"].map(String::from).to_vec())),
+    ]
+}
+
+fn parse(game_state: &GameState, text: &str) -> Result<Condition, String> {
+    parse_condition(text, &GameNames(game_state, variables(), people()))
+        .map_err(|e| format!("{}: {}", e.offset, e.message))
+}
+
+fn holds(game_state: &GameState, text: &str) -> bool {
+    parse(game_state, text).unwrap().holds(game_state)
+}
+
+#[test]
+fn conditions_read_the_game_state() {
+    let mut game_state = game();
+    assert!(holds(&game_state, "has(helmet) && oxygen_percent < 25 && !lab_unlocked"));
+    assert!(holds(&game_state, "at(crater_rim) || at(lab)"));
+    assert!(!holds(&game_state, "visited(lab) || visits(crater_rim) > 0"));
+    assert!(holds(&game_state, "central_trust == \"neutral\" && here(helmet)"));
+    assert!(!holds(&game_state, "!(has(helmet) || lab_unlocked)"));
+
+    game_state.set_variable("oxygen_percent", Value::Int(40));
+    game_state.set_variable("lab_unlocked", Value::Bool(true));
+    assert!(holds(&game_state, "oxygen_percent >= 40 && lab_unlocked == true"));
+    assert!(!holds(&game_state, "has(helmet) && !lab_unlocked || oxygen_percent != 40"));
+}
+
+#[test]
+fn conditions_read_where_people_are() {
+    let mut game_state = game();
+    assert!(holds(&game_state, "!present(central) && !present(fixer)"));
+    assert!(holds(&game_state, "state(fixer) == \"standby\""));
+
+    game_state.location = LocationID::new("quarters");
+    assert!(holds(&game_state, "present(fixer)"));
+}
+
+#[test]
+fn conditions_describe_themselves() {
+    let game_state = game();
+    for text in [
+        "has(helmet) && oxygen_percent < 20 && !visited(central_diagnostics)",
+        "(at(lab) || here(sample_case)) && central_trust != \"hostile\"",
+        "!(visits(corridor) >= 2)",
+        "present(fixer) && state(fixer) == \"escorting\"",
+    ] {
+        assert_eq!(parse(&game_state, text).unwrap().describe(), text);
+    }
+}
+
+#[test]
+fn mistakes_are_reported_where_they_are() {
+    let game_state = game();
+    let error = |text| parse(&game_state, text).unwrap_err();
+    assert_eq!(error("has(crowbar)"), "4: unknown item \"crowbar\"");
+    assert_eq!(error("visited(moon) && lab_unlocked"), "8: unknown location or dialogue node \"moon\"");
+    assert_eq!(error("!oxygen"), "1: unknown variable \"oxygen\"");
+    assert_eq!(error("oxygen_percent"), "0: variable \"oxygen_percent\" is int, compare it with something");
+    assert_eq!(error("central_trust == \"friendly\""), "0: \"friendly\" isn't one of hostile, wary, neutral, trusting");
+    assert_eq!(error("lab_unlocked < 3"), "0: \"<\" only compares numbers");
+    assert_eq!(error("oxygen_percent == true"), "0: can't compare int with bool");
+    assert_eq!(error("oxygen_percent = 3"), "15: use == to compare");
+    assert_eq!(error("(has(helmet)"), "12: the condition ends too soon");
+    assert_eq!(error("has(helmet) lab_unlocked"), "12: unexpected \"lab_unlocked\"");
+    assert_eq!(error("present(watcher)"), "8: unknown person \"watcher\"");
+    assert_eq!(error("state(fixer) == \"asleep\""), "0: \"asleep\" isn't one of standby, escorting");
+    assert_eq!(error("wear(helmet)"), "0: unknown function \"wear\"");
+    assert_eq!(error(""), "0: the condition is empty");
+}
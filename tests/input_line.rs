@@ -0,0 +1,82 @@
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use selenic_echo::game::game_state::GameState;
+use selenic_echo::parser::input_line::InputLine;
+use selenic_echo::parser::process::Parser;
+use selenic_echo::printer::Printer;
+use std::path::Path;
+
+#[test]
+fn editing_at_the_caret() {
+    let mut line = InputLine::new();
+    line.insert("TAKE HELMT");
+    line.left();
+    line.insert("E");
+    assert_eq!(line.text(), "TAKE HELMET");
+    assert_eq!(line.before_caret(), "TAKE HELME");
+
+    line.home();
+    line.delete();
+    line.insert("M");
+    assert_eq!(line.text(), "MAKE HELMET");
+
+    line.end();
+    line.delete_word();
+    assert_eq!(line.text(), "MAKE ");
+    line.insert("a\r\nb");
+    assert_eq!(line.text(), "MAKE ab");
+
+    line.set("GO NÖRTH");
+    line.backspace();
+    line.left();
+    line.left();
+    line.backspace();
+    assert_eq!(line.text(), "GO NRT");
+}
+
+#[test]
+fn recalling_earlier_lines() {
+    let mut line = InputLine::new();
+    for text in ["LOOK", "NORTH", "NORTH", "TAKE CARD"] {
+        line.set(text);
+        line.remember();
+    }
+    line.set("INV");
+
+    line.recall_previous();
+    assert_eq!(line.text(), "TAKE CARD");
+    line.recall_previous();
+    assert_eq!(line.text(), "NORTH");
+    line.recall_previous();
+    line.recall_previous();
+    assert_eq!(line.text(), "LOOK");
+
+    line.recall_next();
+    line.recall_next();
+    assert_eq!(line.text(), "TAKE CARD");
+    line.recall_next();
+    assert_eq!(line.text(), "INV");
+}
+
+fn completed(line: &str, parser: &mut Parser, game_state: &GameState) -> String {
+    parser.edit_line().set(line);
+    parser.process_tab(game_state);
+    parser.edit_line().text().to_string()
+}
+
+#[test]
+fn completing_whole_names_of_what_is_at_hand() {
+    let mut game_state = GameState::new(load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(CONTENT_DIR)).unwrap());
+    let mut parser = Parser::new();
+    let mut printer = Printer::new();
+    assert_eq!(completed("LOOK AT OXY", &mut parser, &game_state), "LOOK AT OXYGEN GAUGE");
+    assert_eq!(completed("LOOK AT OXYGEN G", &mut parser, &game_state), "LOOK AT OXYGEN GAUGE");
+    assert_eq!(completed("PICK U", &mut parser, &game_state), "PICK UP");
+    assert_eq!(completed("TALK TO CEN", &mut parser, &game_state), "TALK TO CENTRAL");
+    // The drone is in the quarters, and finishing its name out here would give it away
+    assert_eq!(completed("TALK TO FIX", &mut parser, &game_state), "TALK TO FIX");
+
+    for line in ["DIFFICULTY STORY", "DOWN", "IN", "NORTH", "WEST"] {
+        parser.submit(line, &mut game_state, &mut printer);
+    }
+    assert_eq!(completed("TALK TO FIX", &mut parser, &game_state), "TALK TO FIXER");
+}
@@ -0,0 +1,64 @@
+use selenic_echo::game::variables::{Value, Variable, VariableType, Variables};
+use selenic_echo::markup::{parse, QueueItem};
+use selenic_echo::printer::PrintStyle;
+
+fn words(text: &str) -> Vec<String> {
+    parse(text, PrintStyle::Normal)
+        .unwrap()
+        .iter()
+        .map(|item| match item {
+            QueueItem::Word(runs) => runs.iter().map(|run| run.text.clone()).collect::<Vec<String>>().join("|"),
+            QueueItem::LineBreak => "<br>".to_string(),
+            QueueItem::Paragraph => "\n".to_string(),
+            QueueItem::Pause(millis) => format!("<pause={}>", millis),
+        })
+        .collect()
+}
+
+fn error(text: &str) -> String {
+    parse(text, PrintStyle::Normal).unwrap_err().message
+}
+
+#[test]
+fn tags_split_runs_not_words() {
+    assert_eq!(words("a door marked <b>LAB</b>."), ["a", "door", "marked", "LAB|."]);
+    assert_eq!(words("one<br>two\nthree <pause=800>"), ["one", "<br>", "two", "\n", "three", "<pause=800>"]);
+    assert_eq!(words("1 << 2"), ["1", "<", "2"]);
+}
+
+#[test]
+fn nested_formats() {
+    let items = parse("<ai><b>BEEP</b> <i>beep</i></ai> <color=#ff8000>ok</color>", PrintStyle::Normal).unwrap();
+    let formats = items
+        .iter()
+        .map(|item| match item {
+            QueueItem::Word(runs) => runs[0].format.clone(),
+            _ => panic!("expected a word"),
+        })
+        .collect::<Vec<_>>();
+    assert!(formats[0].bold && formats[0].style == PrintStyle::AI);
+    assert!(formats[1].italic && !formats[1].bold && formats[1].style == PrintStyle::AI);
+    assert_eq!(formats[2].style, PrintStyle::Normal);
+    assert_eq!(formats[2].color, Some((255, 128, 0)));
+}
+
+#[test]
+fn mistakes_are_reported() {
+    assert_eq!(error("<b>bold"), "<b> is never closed");
+    assert_eq!(error("<b><i>x</b></i>"), "</b> found where </i> was expected");
+    assert_eq!(error("x</b>"), "</b> closes a tag that was never opened");
+    assert_eq!(error("<blink>x</blink>"), "unknown tag <blink>");
+    assert_eq!(error("<pause>"), "<pause> needs a value");
+    assert_eq!(error("<color=mauve>x</color>"), "unknown colour \"mauve\"");
+    assert_eq!(error("<b x"), "a tag is missing its >");
+}
+
+#[test]
+fn variables_are_shown_as_they_are() {
+    let variables = Variables::new(vec![Variable {
+        name: "callsign".to_string(),
+        kind: VariableType::String,
+        start: Value::Text("<b>Echo & Co".to_string()),
+    }]);
+    assert_eq!(words(&variables.interpolate("Hello {callsign}.")), ["Hello", "<b>Echo", "&", "Co."]);
+}
@@ -0,0 +1,91 @@
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use selenic_echo::game::game_state::GameState;
+use selenic_echo::parser::process::Parser;
+use selenic_echo::printer::Printer;
+use selenic_echo::tty::respond;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn game(save_path: &Path) -> (GameState, Parser, Printer) {
+    let mut game_state = GameState::new(load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(CONTENT_DIR)).unwrap());
+    let mut printer = Printer::new();
+    game_state.save_path = save_path.to_path_buf();
+    game_state.start(&mut printer);
+    (game_state, Parser::new(), printer)
+}
+
+/// A save file of its own for each test, so they can run side by side.
+fn save_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("selenic-echo-{}-{}.save", name, std::process::id()))
+}
+
+/// Everything a save is meant to keep besides the transcript, in a form that can be compared.
+fn snapshot(game_state: &GameState) -> Vec<String> {
+    let mut locked = game_state.locked_exits.iter().map(|exit| format!("{:?}", exit)).collect::<Vec<_>>();
+    locked.sort();
+    let mut people = game_state.person_states.iter().map(|person| format!("{:?}", person)).collect::<Vec<_>>();
+    people.sort();
+    vec![
+        format!("{:?}", game_state.location),
+        format!("{:?}", game_state.items.placements()),
+        format!("{:?}", game_state.variables.values()),
+        format!("{:?}", locked),
+        format!("{:?}", people),
+    ]
+}
+
+#[test]
+fn a_restored_game_is_the_game_that_was_saved() {
+    let path = save_path("round-trip");
+    let (mut game_state, mut parser, mut printer) = game(&path);
+    for line in [
+        "DIFFICULTY STORY",
+        "DOWN",
+        "IN",
+        "NORTH",
+        "WEST",
+        "TAKE THE TABLET",
+        "TALK TO FIXER",
+        "1",
+        "EAST",
+        "TALK TO CENTRAL",
+        "3",
+        "YES",
+        "4",
+    ] {
+        respond(line, &mut parser, &mut game_state, &mut printer, false);
+    }
+    let saved_transcript = transcript(&printer);
+    let saved = flatten(respond("SAVE", &mut parser, &mut game_state, &mut printer, false));
+    assert!(saved.contains("Game saved."), "{}", saved);
+    let before = snapshot(&game_state);
+
+    let (mut restored, mut parser, mut printer) = game(&path);
+    assert_ne!(snapshot(&restored), before);
+    let response = flatten(respond("RESTORE", &mut parser, &mut restored, &mut printer, false));
+    assert!(response.contains("Game restored."), "{}", response);
+    assert_eq!(snapshot(&restored), before);
+    // Everything up to the SAVE command, which is echoed before the game is written
+    assert!(transcript(&printer).starts_with(&saved_transcript), "{:?}", transcript(&printer));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn an_old_save_is_refused_by_its_version() {
+    let path = save_path("old-version");
+    fs::write(&path, "version = 1\nlocation = \"crater_rim\"\n").unwrap();
+    let (mut game_state, mut parser, mut printer) = game(&path);
+    let response = flatten(respond("RESTORE", &mut parser, &mut game_state, &mut printer, false));
+    assert!(response.contains("save file is version 1, this game reads version"), "{}", response);
+
+    fs::remove_file(&path).unwrap();
+}
+
+fn transcript(printer: &Printer) -> Vec<String> {
+    printer.transcript().iter().map(|entry| entry.text.clone()).collect()
+}
+
+fn flatten(text: String) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
@@ -0,0 +1,83 @@
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use selenic_echo::dialogue::logic::DialoguePersonID;
+use selenic_echo::game::game_state::GameState;
+use selenic_echo::game::oxygen::Difficulty;
+use selenic_echo::game::triggers::Trigger;
+use selenic_echo::printer::Printer;
+use selenic_echo::tty::render;
+use std::path::Path;
+
+/// The real game on story difficulty, so running out of oxygen doesn't cut a long wait short.
+fn game() -> (GameState, Printer) {
+    let mut game_state = GameState::new(load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(CONTENT_DIR)).unwrap());
+    let mut printer = Printer::new();
+    game_state.difficulty = Difficulty::Story;
+    game_state.start(&mut printer);
+    printer.drain();
+    (game_state, printer)
+}
+
+fn said(printer: &mut Printer) -> String {
+    render(&printer.drain(), false)
+}
+
+fn trigger(id: &str, text: Option<&str>, talk: Option<DialoguePersonID>) -> Trigger {
+    Trigger {
+        id: id.to_string(),
+        event: None,
+        condition: None,
+        narration: None,
+        text: text.map(str::to_string),
+        talk,
+        effects: Vec::new(),
+    }
+}
+
+#[test]
+fn turn_triggers_fire_once_enough_commands_have_run() {
+    let (mut game_state, mut printer) = game();
+    for _ in 0..39 {
+        game_state.end_turn(&mut printer);
+    }
+    assert!(!game_state.fired.contains("radio_static"));
+    said(&mut printer);
+
+    game_state.end_turn(&mut printer);
+    assert!(game_state.fired.contains("radio_static"));
+    assert!(said(&mut printer).contains("Your radio crackles"));
+
+    game_state.end_turn(&mut printer);
+    assert!(!said(&mut printer).contains("Your radio crackles"));
+}
+
+#[test]
+fn timed_triggers_fire_once_enough_time_has_passed() {
+    let (mut game_state, mut printer) = game();
+    game_state.pass_time(599.0, &mut printer);
+    assert!(!game_state.fired.contains("lights_flicker"));
+
+    game_state.pass_time(1.0, &mut printer);
+    assert!(game_state.fired.contains("lights_flicker"));
+    assert!(said(&mut printer).contains("The strip lights stutter"));
+}
+
+#[test]
+fn a_conversation_holds_back_the_triggers_after_it() {
+    let (mut game_state, mut printer) = game();
+    game_state.triggers = vec![
+        trigger("call", None, Some(DialoguePersonID::Central)),
+        trigger("after", Some("The radio clicks off."), None),
+    ];
+    game_state.end_turn(&mut printer);
+    assert!(game_state.in_conversation());
+    assert!(game_state.fired.contains("call"));
+    assert!(!game_state.fired.contains("after"));
+    let text = said(&mut printer);
+    assert!(text.contains("System Status: Active."));
+    assert!(!text.contains("The radio clicks off."));
+
+    game_state.conversation = None;
+    game_state.end_turn(&mut printer);
+    assert!(game_state.fired.contains("after"));
+    assert!(said(&mut printer).contains("The radio clicks off."));
+}
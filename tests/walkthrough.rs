@@ -0,0 +1,125 @@
+use selenic_echo::content::loader::{load, CONTENT_DIR};
+use selenic_echo::dialogue::logic::DialoguePersonID;
+use selenic_echo::game::game_state::GameState;
+use selenic_echo::parser::process::Parser;
+use selenic_echo::printer::Printer;
+use selenic_echo::tty::{render, respond};
+use std::fs;
+use std::path::Path;
+
+const WALKTHROUGHS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/walkthroughs");
+
+// Walkthrough files:
+//   # comment
+//   > COMMAND        typed by the player
+//   anything else    must appear in the response to the command above it
+//   = CHECK VALUE    checked against the game state once every command has run
+// Lines before the first command are checked against the opening narration.
+// Played through the terminal frontend, so they run without a window, or SDL2 and Skia with
+// --no-default-features.
+
+#[test]
+fn walkthroughs() {
+    let mut paths = fs::read_dir(WALKTHROUGHS)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty(), "no walkthroughs found in {}", WALKTHROUGHS);
+
+    let failures = paths.iter().flat_map(|path| run(path)).collect::<Vec<String>>();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+fn run(path: &Path) -> Vec<String> {
+    let name = path.file_name().unwrap().to_string_lossy();
+    let script = fs::read_to_string(path).unwrap();
+
+    let content = load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(CONTENT_DIR)).unwrap();
+    let mut game_state = GameState::new(content);
+    let mut parser = Parser::new();
+    let mut printer = Printer::new();
+    game_state.start(&mut printer);
+
+    let mut failures = Vec::new();
+    let mut command = "(start)".to_string();
+    let mut response = flatten(&render(&printer.drain(), false));
+    let mut checks = Vec::new();
+    for (number, line) in script.lines().enumerate().map(|(index, line)| (index + 1, line.trim())) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(typed) = line.strip_prefix('>') {
+            command = typed.trim().to_string();
+            response = flatten(&respond(&command, &mut parser, &mut game_state, &mut printer, false));
+        } else if let Some(check) = line.strip_prefix('=') {
+            checks.push((number, check.trim().to_string()));
+        } else if !response.contains(&flatten(line)) {
+            failures
+                .push(format!("{}:{}: after {} expected \"{}\" in:\n    {}", name, number, command, line, response));
+        }
+    }
+
+    for (number, check) in checks {
+        if let Err(e) = check_state(&check, &game_state) {
+            failures.push(format!("{}:{}: {}", name, number, e));
+        }
+    }
+    failures
+}
+
+/// Line breaks don't matter, only the words.
+fn flatten(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Checks read like "location lab", "carrying access_card", "not flag lab_unlocked", "variable central_trust wary"
+/// or "not ended", ids as in the content files.
+fn check_state(check: &str, game_state: &GameState) -> Result<(), String> {
+    let (expected, check) = match check.strip_prefix("not ") {
+        Some(rest) => (false, rest),
+        None => (true, check),
+    };
+    let (what, value) = check.split_once(' ').unwrap_or((check, ""));
+
+    let actual = match what {
+        "location" => format!("{:?}", game_state.location) == value,
+        "carrying" => game_state
+            .items
+            .placements()
+            .iter()
+            .any(|(id, _)| format!("{:?}", id) == value && game_state.items.is_carried(*id)),
+        "flag" => game_state.variables.is_true(value),
+        "variable" => {
+            let (name, value) = value.split_once(' ').unwrap_or((value, ""));
+            game_state.variables.get(name).is_some_and(|actual| actual.to_string() == value)
+        }
+        "visited" => game_state.visits.keys().any(|id| format!("{:?}", id) == value),
+        "talked" => game_state.dialogue_visits.keys().any(|id| format!("{:?}", id) == value),
+        "conversation" => game_state.in_conversation(),
+        "ended" => game_state.game_over,
+        "triggered" => game_state.fired.contains(value),
+        "present" => DialoguePersonID::find(value).is_some_and(|person| game_state.is_present(person)),
+        "state" => {
+            let (person, state) = value.split_once(' ').unwrap_or((value, ""));
+            DialoguePersonID::find(person)
+                .and_then(|person| game_state.person_states.get(&person))
+                .is_some_and(|person| person.state == state)
+        }
+        _ => return Err(format!("unknown check \"{}\"", what)),
+    };
+    if actual != expected {
+        return Err(format!(
+            "expected {}{} {}",
+            if expected {
+                ""
+            } else {
+                "not "
+            },
+            what,
+            value
+        ));
+    }
+    Ok(())
+}